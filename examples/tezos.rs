@@ -1,20 +1,5 @@
 use irmin::*;
 
-fn list_path<T: Contents>(store: &Store<T>, path: Path) -> Result<(), Error> {
-    for k in store.list(&path)? {
-        let p = path.append_path(&k)?;
-
-        // If the store has contents at `p` then print the path
-        if store.mem(&p) {
-            println!("{}", p.to_string()?);
-        } else {
-            list_path(store, p)?;
-        }
-    }
-
-    Ok(())
-}
-
 fn main() -> Result<(), Error> {
     let args: Vec<_> = std::env::args().collect();
 
@@ -27,19 +12,20 @@ fn main() -> Result<(), Error> {
     let mut config = Config::<IrminString>::tezos()?;
     assert!(config.set_root(&args[1]));
 
-    // Initialize the repo
-    let repo = Repo::new(config)?;
+    // Initialize the repo, the context is only read so open it in read-only mode
+    let repo = Repo::open_readonly(config)?;
 
     // Resolve commit
-    let hash = Hash::of_string(&repo, &args[2])?;
-    let commit = Commit::of_hash(&repo, &hash)?.expect("Commit not found");
+    let hash = repo.hash(&args[2])?;
+    let commit = repo.commit_of_hash(&hash)?.expect("Commit not found");
 
     // Open the store
-    let store = Store::of_commit(&repo, &commit)?;
+    let store = repo.of_commit(&commit)?;
 
-    // List contract paths
-    let path = repo.path(&["data", "contracts"])?;
-    list_path(&store, path)?;
+    // List contract paths, values aren't read
+    for path in store.glob_paths("data/contracts/**")? {
+        println!("{}", path?);
+    }
 
     Ok(())
 }
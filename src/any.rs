@@ -0,0 +1,286 @@
+use crate::internal::*;
+
+use crate::repo::branches;
+
+/// Repo with a content type that is only known at runtime
+pub struct AnyRepo {
+    ptr: *mut IrminRepo,
+    config: *mut IrminConfig,
+    hash_type: HashType,
+    context: ContentsContext,
+    content_type: ContentType,
+}
+
+impl<T: Contents> From<Repo<T>> for AnyRepo {
+    fn from(repo: Repo<T>) -> AnyRepo {
+        // The `AnyRepo` takes ownership of the repo and config pointers and the context
+        let repo = std::mem::ManuallyDrop::new(repo);
+        AnyRepo {
+            ptr: repo.ptr,
+            config: repo.config.ptr,
+            hash_type: repo.config.hash_type,
+            context: unsafe { std::ptr::read(&repo.config.context) },
+            content_type: T::content_type(),
+        }
+    }
+}
+
+impl Drop for AnyRepo {
+    fn drop(&mut self) {
+        unsafe {
+            irmin_repo_free(self.ptr);
+            irmin_config_free(self.config);
+        }
+    }
+}
+
+impl AnyRepo {
+    /// Open a repo using the backend and content type specified by a `ConfigFile`, string
+    /// contents are used when no content type is specified
+    pub fn new(config: &ConfigFile) -> Result<AnyRepo, Error> {
+        match config.contents.unwrap_or(ContentType::String) {
+            ContentType::String => Repo::new(config.config::<IrminString>()?).map(AnyRepo::from),
+            ContentType::Json => Repo::new(config.config::<Json>()?).map(AnyRepo::from),
+            ContentType::JsonValue => Repo::new(config.config::<JsonValue>()?).map(AnyRepo::from),
+        }
+    }
+
+    /// Open a repo using a TOML or JSON configuration file
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<AnyRepo, Error> {
+        AnyRepo::new(&ConfigFile::load(path)?)
+    }
+
+    /// Content type of the repo
+    pub fn content_type(&self) -> ContentType {
+        self.content_type
+    }
+
+    /// Convert to a repo with a static content type, the original `AnyRepo` is returned if `T`
+    /// doesn't match the content type of the repo
+    pub fn downcast<T: Contents>(self) -> Result<Repo<T>, AnyRepo> {
+        if T::content_type() != self.content_type {
+            return Err(self);
+        }
+        // Ownership of the pointers and the context moves to the new `Repo`
+        let repo = std::mem::ManuallyDrop::new(self);
+        Ok(Repo {
+            config: Config {
+                ptr: repo.config,
+                hash_type: repo.hash_type,
+                context: unsafe { std::ptr::read(&repo.context) },
+                _t: std::marker::PhantomData,
+            },
+            ptr: repo.ptr,
+        })
+    }
+
+    fn untyped(&self) -> UntypedRepo<'_> {
+        UntypedRepo {
+            ptr: self.ptr,
+            hash_type: self.hash_type,
+            context: self.context.clone(),
+            owner: None,
+            _t: std::marker::PhantomData,
+        }
+    }
+
+    /// Get a list of all branches
+    pub fn branches(&self) -> Result<Vec<IrminString>, Error> {
+        branches(self.ptr)
+    }
+
+    /// Create a new path
+    pub fn path(&self, s: &[impl AsRef<str>]) -> Result<Path<'_>, Error> {
+        Path::of_segments(&self.untyped(), s)
+    }
+
+    /// Create commit info
+    pub fn info(
+        &self,
+        author: impl AsRef<str>,
+        message: impl AsRef<str>,
+    ) -> Result<Info<'_>, Error> {
+        Info::of_untyped(&self.untyped(), author, message)
+    }
+
+    fn check_type<T: Contents>(&self) -> Result<(), Error> {
+        if T::content_type() != self.content_type {
+            return Err(Error::Type(format!(
+                "expected {:?} contents but the repo has {:?} contents",
+                T::content_type(),
+                self.content_type
+            )));
+        }
+        Ok(())
+    }
+
+    fn value_to_json(&self, v: &Value) -> Result<JsonValue, Error> {
+        match self.content_type {
+            ContentType::String => String::from_value(v).map(JsonValue::String),
+            ContentType::Json => Json::from_value(v).map(JsonValue::Object),
+            ContentType::JsonValue => JsonValue::from_value(v),
+        }
+    }
+
+    fn json_to_value(&self, v: &JsonValue) -> Result<Value, Error> {
+        match (self.content_type, v) {
+            (ContentType::String, JsonValue::String(s)) => Value::string(s),
+            (ContentType::String, v) => Value::string(serde_json::to_string(v)?),
+            (ContentType::Json, v) => serde_json::from_value::<Json>(v.clone())?.to_value(),
+            (ContentType::JsonValue, v) => v.to_value(),
+        }
+    }
+}
+
+/// Store with a content type that is only known at runtime, values are returned as `Value`
+/// or `serde_json::Value`
+pub struct AnyStore<'a> {
+    pub ptr: *mut Irmin,
+    pub repo: &'a AnyRepo,
+}
+
+impl<'a> AnyStore<'a> {
+    /// Open the main branch of a store
+    pub fn new(repo: &'a AnyRepo) -> Result<AnyStore<'a>, Error> {
+        let ptr = unsafe { irmin_main(repo.ptr) };
+        check!(repo.ptr, ptr);
+        Ok(AnyStore { ptr, repo })
+    }
+
+    /// Specify the branch to open
+    pub fn of_branch(repo: &'a AnyRepo, branch: impl AsRef<str>) -> Result<AnyStore<'a>, Error> {
+        let branch = cstring(branch);
+        let ptr = unsafe { irmin_of_branch(repo.ptr, branch.as_ptr() as *mut _) };
+        check!(repo.ptr, ptr);
+        Ok(AnyStore { ptr, repo })
+    }
+
+    /// Specify the commit to open, the commit must have the content type of the repo
+    pub fn of_commit<T: Contents>(
+        repo: &'a AnyRepo,
+        commit: &Commit<T>,
+    ) -> Result<AnyStore<'a>, Error> {
+        repo.check_type::<T>()?;
+        let ptr = unsafe { irmin_of_commit(repo.ptr, commit.ptr) };
+        check!(repo.ptr, ptr);
+        Ok(AnyStore { ptr, repo })
+    }
+
+    /// Find the value associated with the given path
+    pub fn find(&self, path: impl IntoPath) -> Result<Option<Value>, Error> {
+        let path = path.into_path(&self.repo.untyped())?;
+        let r = unsafe { irmin_find(self.ptr, path.ptr) };
+        check_opt!(self.repo.ptr, r);
+        let ty = self.repo.content_type.ty()?;
+        Ok(Some(Value {
+            ptr: r as *mut _,
+            ty,
+        }))
+    }
+
+    /// Find the value associated with the given path and convert it to JSON, string contents
+    /// are returned as JSON strings
+    pub fn find_json(&self, path: impl IntoPath) -> Result<Option<JsonValue>, Error> {
+        match self.find(path)? {
+            Some(v) => self.repo.value_to_json(&v).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Set a value, creating a new commit. The value must have the content type of the repo
+    pub fn set(&mut self, path: impl IntoPath, value: &Value, info: Info) -> Result<bool, Error> {
+        let path = path.into_path(&self.repo.untyped())?;
+        let expected = self.repo.content_type.ty()?.name()?;
+        let name = value.ty.name()?;
+        if name != expected {
+            return Err(Error::Type(format!(
+                "expected {} but got {}",
+                expected.as_str(),
+                name.as_str()
+            )));
+        }
+        unsafe {
+            let r = irmin_set(self.ptr, path.ptr, value.ptr as *mut _, info.ptr);
+            check!(self.repo.ptr, r, false);
+            Ok(r)
+        }
+    }
+
+    /// Set a JSON value, creating a new commit. For string contents, JSON strings are stored
+    /// as-is and other values are stored using their JSON encoding
+    pub fn set_json(
+        &mut self,
+        path: impl IntoPath,
+        value: &JsonValue,
+        info: Info,
+    ) -> Result<bool, Error> {
+        let value = self.repo.json_to_value(value)?;
+        self.set(path, &value, info)
+    }
+
+    /// Check for the existence of a value at the given path
+    pub fn mem(&self, path: impl IntoPath) -> Result<bool, Error> {
+        let path = path.into_path(&self.repo.untyped())?;
+        let x = unsafe { irmin_mem(self.ptr, path.ptr) };
+        check!(self.repo.ptr, x, false);
+        Ok(x)
+    }
+
+    /// Check for the existence of a tree at the given path
+    pub fn mem_tree(&self, path: impl IntoPath) -> Result<bool, Error> {
+        let path = path.into_path(&self.repo.untyped())?;
+        let x = unsafe { irmin_mem_tree(self.ptr, path.ptr) };
+        check!(self.repo.ptr, x, false);
+        Ok(x)
+    }
+
+    /// Remove the tree or value associated with the given path
+    pub fn remove(&mut self, path: impl IntoPath, info: Info) -> Result<bool, Error> {
+        let path = path.into_path(&self.repo.untyped())?;
+        let x = unsafe { irmin_remove(self.ptr, path.ptr, info.ptr) };
+        check!(self.repo.ptr, x, false);
+        Ok(x)
+    }
+
+    /// Get current head commit, `T` must match the content type of the repo
+    pub fn head<T: Contents>(&self) -> Result<Option<Commit<'a, T>>, Error> {
+        self.repo.check_type::<T>()?;
+        let ptr = unsafe { irmin_get_head(self.ptr) };
+        check_opt!(self.repo.ptr, ptr);
+        Ok(Some(Commit {
+            ptr,
+            repo: self.repo.untyped(),
+            _t: std::marker::PhantomData,
+        }))
+    }
+
+    /// List paths
+    pub fn list(&self, path: impl IntoPath) -> Result<Vec<Path<'a>>, Error> {
+        let path = path.into_path(&self.repo.untyped())?;
+        let repo = self.repo;
+        let p = unsafe { irmin_list(self.ptr, path.ptr) };
+        check!(repo.ptr, p);
+        let len = unsafe { irmin_path_array_length(repo.ptr, p) };
+        let mut dest = Vec::new();
+        for i in 0..len {
+            let path = unsafe { irmin_path_array_get(repo.ptr, p, i) };
+            if path.is_null() {
+                continue;
+            }
+            dest.push(Path {
+                ptr: path,
+                repo: repo.untyped(),
+            })
+        }
+
+        unsafe { irmin_path_array_free(p) }
+
+        Ok(dest)
+    }
+}
+
+impl<'a> Drop for AnyStore<'a> {
+    fn drop(&mut self) {
+        unsafe { irmin_free(self.ptr) }
+    }
+}
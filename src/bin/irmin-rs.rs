@@ -0,0 +1,532 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use irmin::*;
+
+/// Inspect and edit irmin stores from the command line
+#[derive(Parser)]
+#[command(name = "irmin-rs", version)]
+struct Args {
+    /// Storage backend
+    #[arg(short, long, value_enum, default_value_t = Backend::Git)]
+    backend: Backend,
+
+    /// Root directory of the store
+    #[arg(short, long)]
+    root: Option<String>,
+
+    /// Hash function, only used by the pack, fs and mem backends
+    #[arg(long, value_enum)]
+    hash: Option<Hash>,
+
+    /// Content type, defaults to `string`, which is the only type supported by the tezos backend
+    #[arg(short, long, value_enum)]
+    contents: Option<Contents>,
+
+    /// Branch to operate on
+    #[arg(long, default_value = "main")]
+    branch: String,
+
+    /// Commit author
+    #[arg(long, default_value = "irmin-rs")]
+    author: String,
+
+    /// Commit message, a message is generated from the command by default
+    #[arg(short, long)]
+    message: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Backend {
+    Git,
+    Pack,
+    Fs,
+    Mem,
+    Tezos,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Hash {
+    Blake2b,
+    Blake2s,
+    Rmd160,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Contents {
+    String,
+    Json,
+    JsonValue,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the value stored at a path
+    Get { path: String },
+
+    /// Store a value at a path
+    Set { path: String, value: String },
+
+    /// Remove a path
+    Rm { path: String },
+
+    /// List the children of a path
+    Ls { path: Option<String> },
+
+    /// Print the tree below a path
+    Tree { path: Option<String> },
+
+    /// Print the commit history of the branch
+    Log {
+        /// Maximum number of commits to print
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+    },
+
+    /// Print the differences between two branches or commit hashes
+    Diff { a: String, b: String },
+
+    /// List all branches
+    Branches,
+
+    /// Merge another branch into the current branch
+    Merge { branch: String },
+
+    /// Pull from a remote repository
+    Pull {
+        url: String,
+        #[arg(long)]
+        depth: Option<i32>,
+    },
+
+    /// Push to a remote repository
+    Push {
+        url: String,
+        #[arg(long)]
+        depth: Option<i32>,
+    },
+
+    /// Dump every value of a branch or commit as a JSON object keyed by path
+    Snapshot { commit: Option<String> },
+}
+
+/// Conversion between contents and their command line representation
+trait CliContents: irmin::Contents {
+    fn parse(s: &str) -> Result<Self, Error>;
+    fn show(&self) -> String;
+    fn to_json(&self) -> JsonValue;
+}
+
+impl CliContents for IrminString {
+    fn parse(s: &str) -> Result<Self, Error> {
+        IrminString::new(s)
+    }
+
+    fn show(&self) -> String {
+        String::from_utf8_lossy(self.as_slice()).into_owned()
+    }
+
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.show())
+    }
+}
+
+impl CliContents for String {
+    fn parse(s: &str) -> Result<Self, Error> {
+        Ok(s.to_string())
+    }
+
+    fn show(&self) -> String {
+        self.clone()
+    }
+
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.clone())
+    }
+}
+
+impl CliContents for Json {
+    fn parse(s: &str) -> Result<Self, Error> {
+        serde_json::from_str(s).map_err(Error::from)
+    }
+
+    fn show(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(self.clone())
+    }
+}
+
+impl CliContents for JsonValue {
+    fn parse(s: &str) -> Result<Self, Error> {
+        serde_json::from_str(s).map_err(Error::from)
+    }
+
+    fn show(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+    fn to_json(&self) -> JsonValue {
+        self.clone()
+    }
+}
+
+impl From<Hash> for HashType {
+    fn from(h: Hash) -> HashType {
+        match h {
+            Hash::Blake2b => HashType::Blake2b,
+            Hash::Blake2s => HashType::Blake2s,
+            Hash::Rmd160 => HashType::Rmd160,
+            Hash::Sha1 => HashType::Sha1,
+            Hash::Sha224 => HashType::Sha224,
+            Hash::Sha256 => HashType::Sha256,
+            Hash::Sha384 => HashType::Sha384,
+            Hash::Sha512 => HashType::Sha512,
+        }
+    }
+}
+
+impl From<Backend> for irmin::Backend {
+    fn from(b: Backend) -> irmin::Backend {
+        match b {
+            Backend::Git => irmin::Backend::Git,
+            Backend::Pack => irmin::Backend::Pack,
+            Backend::Fs => irmin::Backend::Fs,
+            Backend::Mem => irmin::Backend::Mem,
+            Backend::Tezos => irmin::Backend::Tezos,
+        }
+    }
+}
+
+impl From<Contents> for ContentType {
+    fn from(c: Contents) -> ContentType {
+        match c {
+            Contents::String => ContentType::String,
+            Contents::Json => ContentType::Json,
+            Contents::JsonValue => ContentType::JsonValue,
+        }
+    }
+}
+
+/// Build the store configuration using `ConfigFile`, which rejects options that aren't
+/// supported by the backend
+fn config<T: irmin::Contents>(args: &Args) -> Result<Config<T>, Error> {
+    let mut file = ConfigFile::new(args.backend.into());
+    file.root = args.root.as_ref().map(std::path::PathBuf::from);
+    file.hash = args.hash.map(HashType::from);
+    file.contents = args.contents.map(ContentType::from);
+    file.config()
+}
+
+fn fail(msg: impl AsRef<str>) -> ! {
+    eprintln!("irmin-rs: {}", msg.as_ref());
+    std::process::exit(1)
+}
+
+fn path<'a, T: irmin::Contents>(repo: &'a Repo<T>, s: Option<&str>) -> Result<Path<'a>, Error> {
+    match s {
+        Some(s) if !s.is_empty() => Path::from_str(repo, s),
+        _ => Path::empty(repo),
+    }
+}
+
+fn info<'a, T: irmin::Contents>(
+    args: &Args,
+    repo: &'a Repo<T>,
+    default: impl AsRef<str>,
+) -> Result<Info<'a>, Error> {
+    let message = match &args.message {
+        Some(m) => m.as_str(),
+        None => default.as_ref(),
+    };
+    repo.info(&args.author, message)
+}
+
+/// Resolve a branch name or commit hash
+fn resolve<'a, T: irmin::Contents>(repo: &'a Repo<T>, s: &str) -> Result<Commit<'a, T>, Error> {
+    let branches = repo.branches()?;
+    if branches.iter().any(|b| b.as_slice() == s.as_bytes()) {
+        let store = Store::of_branch(repo, s)?;
+        return match store.head()? {
+            Some(c) => Ok(c),
+            None => fail(format!("branch has no commits: {s}")),
+        };
+    }
+
+    let hash = irmin::Hash::of_string(repo, s)?;
+    match Commit::of_hash(repo, &hash)? {
+        Some(c) => Ok(c),
+        None => fail(format!("no such branch or commit: {s}")),
+    }
+}
+
+/// Call `f` with the path and value of every contents below `path`
+fn walk<'a, T: CliContents>(
+    tree: &Tree<'a, T>,
+    path: &Path<'a>,
+    f: &mut impl FnMut(&Path<'a>, T) -> Result<(), Error>,
+) -> Result<(), Error> {
+    if let Some(v) = tree.find(path)? {
+        return f(path, v);
+    }
+
+    for k in tree.list(path)? {
+        let p = path.append_path(&k)?;
+        walk(tree, &p, f)?;
+    }
+    Ok(())
+}
+
+fn contents_map<T: CliContents>(
+    repo: &Repo<T>,
+    tree: &Tree<T>,
+) -> Result<BTreeMap<String, T>, Error> {
+    let mut dest = BTreeMap::new();
+    let root = Path::empty(repo)?;
+    walk(tree, &root, &mut |p, v| {
+        dest.insert(p.to_string()?, v);
+        Ok(())
+    })?;
+    Ok(dest)
+}
+
+fn show_map<T: CliContents>(
+    repo: &Repo<T>,
+    tree: &Tree<T>,
+) -> Result<BTreeMap<String, String>, Error> {
+    let map = contents_map(repo, tree)?;
+    Ok(map.into_iter().map(|(k, v)| (k, v.show())).collect())
+}
+
+/// JSON object mapping each path to its value, JSON contents are included as-is
+fn snapshot<T: CliContents>(repo: &Repo<T>, tree: &Tree<T>) -> Result<JsonValue, Error> {
+    let map = contents_map(repo, tree)?;
+    Ok(JsonValue::Object(
+        map.into_iter().map(|(k, v)| (k, v.to_json())).collect(),
+    ))
+}
+
+fn print_tree<T: CliContents>(tree: &Tree<T>, path: &Path, depth: usize) -> Result<(), Error> {
+    for k in tree.list(path)? {
+        let p = path.append_path(&k)?;
+        let name = k.to_string()?;
+        let indent = "  ".repeat(depth);
+        match tree.find(&p)? {
+            Some(v) => println!("{indent}{name}: {}", v.show()),
+            None => {
+                println!("{indent}{name}/");
+                print_tree(tree, &p, depth + 1)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run<T: CliContents>(args: &Args, config: Config<T>) -> Result<(), Error> {
+    let repo = Repo::new(config)?;
+    let mut store = Store::of_branch(&repo, &args.branch)?;
+
+    match &args.command {
+        Command::Get { path: p } => {
+            let p = path(&repo, Some(p))?;
+            match store.find(&p)? {
+                Some(v) => println!("{}", v.show()),
+                None => fail(format!("not found: {}", p.to_string()?)),
+            }
+        }
+        Command::Set { path: p, value } => {
+            let info = info(args, &repo, format!("set {p}"))?;
+            let p = path(&repo, Some(p))?;
+            let value = T::parse(value)?;
+            if !store.set(&p, &value, info)? {
+                fail("set failed")
+            }
+        }
+        Command::Rm { path: p } => {
+            let info = info(args, &repo, format!("remove {p}"))?;
+            let p = path(&repo, Some(p))?;
+            if !store.remove(&p, info)? {
+                fail("remove failed")
+            }
+        }
+        Command::Ls { path: p } => {
+            let p = path(&repo, p.as_deref())?;
+            for k in store.list(&p)? {
+                let name = k.to_string()?;
+                if store.mem(&p.append_path(&k)?)? {
+                    println!("{name}");
+                } else {
+                    println!("{name}/");
+                }
+            }
+        }
+        Command::Tree { path: p } => {
+            let p = path(&repo, p.as_deref())?;
+            if let Some(tree) = store.find_tree(&p)? {
+                print_tree(&tree, &Path::empty(&repo)?, 0)?;
+            }
+        }
+        Command::Log { limit } => {
+            let mut seen = HashSet::new();
+            let mut queue: VecDeque<_> = store.head()?.into_iter().collect();
+            let mut count = 0;
+            while let Some(commit) = queue.pop_front() {
+                if limit.map(|n| count >= n).unwrap_or(false) {
+                    break;
+                }
+                let hash = commit.hash()?.to_string()?;
+                if !seen.insert(hash.clone()) {
+                    continue;
+                }
+                let info = commit.info()?;
+                println!("commit {hash}");
+                println!("Author: {}", info.author()?.as_str());
+                println!("Date:   {}", info.date());
+                println!();
+                println!("    {}", info.message()?.as_str());
+                println!();
+                queue.extend(commit.parents()?);
+                count += 1;
+            }
+        }
+        Command::Diff { a, b } => {
+            let a = show_map(&repo, &resolve(&repo, a)?.tree()?)?;
+            let b = show_map(&repo, &resolve(&repo, b)?.tree()?)?;
+            for (k, v) in &a {
+                match b.get(k) {
+                    None => println!("- {k}: {v}"),
+                    Some(w) if w != v => {
+                        println!("- {k}: {v}");
+                        println!("+ {k}: {w}");
+                    }
+                    Some(_) => (),
+                }
+            }
+            for (k, v) in &b {
+                if !a.contains_key(k) {
+                    println!("+ {k}: {v}");
+                }
+            }
+        }
+        Command::Branches => {
+            for b in repo.branches()? {
+                println!("{}", String::from_utf8_lossy(b.as_slice()));
+            }
+        }
+        Command::Merge { branch } => {
+            let info = info(args, &repo, format!("merge {branch}"))?;
+            if !store.merge_with_branch(branch, info)? {
+                fail("merge conflict")
+            }
+        }
+        Command::Pull { url, depth } => {
+            let remote = Remote::url(&repo, url)?;
+            let info = info(args, &repo, format!("pull {url}"))?;
+            let commit = store.pull(&remote, *depth, Some(&info))?;
+            println!("{}", commit.hash()?.to_string()?);
+        }
+        Command::Push { url, depth } => {
+            let remote = Remote::url(&repo, url)?;
+            let commit = store.push(&remote, *depth)?;
+            println!("{}", commit.hash()?.to_string()?);
+        }
+        Command::Snapshot { commit } => {
+            let tree = match commit {
+                Some(c) => resolve(&repo, c)?.tree()?,
+                None => store
+                    .find_tree(&Path::empty(&repo)?)?
+                    .unwrap_or(repo.tree()?),
+            };
+            let snapshot = snapshot(&repo, &tree)?;
+            println!("{}", serde_json::to_string_pretty(&snapshot)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+
+    match (args.backend, args.contents) {
+        (Backend::Tezos, _) => run(&args, config::<IrminString>(&args)?),
+        (_, None | Some(Contents::String)) => run(&args, config::<String>(&args)?),
+        (_, Some(Contents::Json)) => run(&args, config::<Json>(&args)?),
+        (_, Some(Contents::JsonValue)) => run(&args, config::<JsonValue>(&args)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args() {
+        use clap::CommandFactory;
+        Args::command().debug_assert();
+
+        let args = Args::try_parse_from([
+            "irmin-rs",
+            "--backend",
+            "mem",
+            "--contents",
+            "json",
+            "set",
+            "a/b",
+            "{}",
+        ])
+        .unwrap();
+        assert!(matches!(args.backend, Backend::Mem));
+        assert!(matches!(args.contents, Some(Contents::Json)));
+        assert!(
+            matches!(args.command, Command::Set { ref path, ref value } if path == "a/b" && value == "{}")
+        );
+        assert!(args.branch == "main");
+
+        let args = Args::try_parse_from(["irmin-rs", "log", "-n", "3"]).unwrap();
+        assert!(matches!(args.command, Command::Log { limit: Some(3) }));
+        assert!(Args::try_parse_from(["irmin-rs", "--backend", "nope", "branches"]).is_err());
+        assert!(Args::try_parse_from(["irmin-rs", "get"]).is_err());
+
+        // Options that the backend doesn't support are rejected by `ConfigFile`
+        let args = Args::try_parse_from(["irmin-rs", "--hash", "sha256", "branches"]).unwrap();
+        assert!(config::<String>(&args).is_err());
+        let args =
+            Args::try_parse_from(["irmin-rs", "-b", "tezos", "-c", "json", "branches"]).unwrap();
+        assert!(config::<IrminString>(&args).is_err());
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<(), Error> {
+        let repo = Repo::new(Config::<JsonValue>::mem(None)?)?;
+        let mut store = Store::new(&repo)?;
+        let a = serde_json::json!({"x": [1, 2]});
+        let b = serde_json::json!("text");
+        store.set("a/b", &a, repo.info("irmin", "set")?)?;
+        store.set("c", &b, repo.info("irmin", "set")?)?;
+
+        let tree = store.find_tree(Path::empty(&repo)?)?.unwrap();
+        let json = snapshot(&repo, &tree)?;
+        assert!(json == serde_json::json!({"a/b": {"x": [1, 2]}, "c": "text"}));
+
+        // Values are included as JSON, not as encoded strings
+        for (k, v) in json.as_object().unwrap() {
+            assert!(store.find(k.as_str())? == Some(v.clone()));
+        }
+
+        let repo = Repo::new(Config::<String>::mem(None)?)?;
+        let mut store = Store::new(&repo)?;
+        store.set("a", &String::from("{}"), repo.info("irmin", "set")?)?;
+        let tree = store.find_tree(Path::empty(&repo)?)?.unwrap();
+        assert!(snapshot(&repo, &tree)? == serde_json::json!({"a": "{}"}));
+        Ok(())
+    }
+}
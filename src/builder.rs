@@ -0,0 +1,162 @@
+use crate::internal::*;
+
+/// Irmin_pack backend options
+pub struct Pack;
+
+/// Irmin_git backend options
+pub struct Git;
+
+/// Irmin_fs backend options
+pub struct Fs;
+
+/// Irmin_mem backend options
+pub struct Mem;
+
+enum Opt {
+    Bool(bool),
+    Int(i64),
+    OptionString(Option<String>),
+}
+
+impl Opt {
+    fn value(&self) -> Result<Value, Error> {
+        match self {
+            Opt::Bool(b) => Value::bool(*b),
+            Opt::Int(i) => Value::int(*i),
+            Opt::OptionString(s) => match s {
+                Some(s) => Value::option(&Type::string()?, Some(&Value::string(s)?)),
+                None => Value::option(&Type::string()?, None),
+            },
+        }
+    }
+}
+
+/// Typed builder for `Config`, the `B` parameter determines which backend options are available
+pub struct ConfigBuilder<T: Contents, B> {
+    config: Config<T>,
+    root: Option<std::path::PathBuf>,
+    options: Vec<(&'static str, Opt)>,
+    _b: std::marker::PhantomData<B>,
+}
+
+impl<T: Contents, B> ConfigBuilder<T, B> {
+    pub(crate) fn wrap(config: Config<T>) -> ConfigBuilder<T, B> {
+        ConfigBuilder {
+            config,
+            root: None,
+            options: Vec::new(),
+            _b: std::marker::PhantomData,
+        }
+    }
+
+    fn opt(mut self, key: &'static str, value: Opt) -> Self {
+        self.options.retain(|(k, _)| *k != key);
+        self.options.push((key, value));
+        self
+    }
+
+    /// Set root path
+    pub fn root(mut self, root: impl AsRef<std::path::Path>) -> Self {
+        self.root = Some(root.as_ref().to_path_buf());
+        self
+    }
+
+    /// Apply all options, returning an error naming the first key that libirmin rejected along
+    /// with the type of the value that was passed
+    pub fn build(self) -> Result<Config<T>, Error> {
+        let mut config = self.config;
+        if let Some(root) = self.root {
+            if !config.set_root(&root) {
+                return Err(Error::Config(String::from("root")));
+            }
+        }
+
+        for (key, opt) in self.options {
+            let v = opt.value()?;
+            if !config.set(key, &v.ty, &v) {
+                return Err(Error::Config(format!(
+                    "{key}: expected a value of type {}",
+                    v.ty.name()?.to_string_lossy()
+                )));
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+impl<T: Contents> ConfigBuilder<T, Pack> {
+    /// Create builder for Irmin_pack store
+    pub fn pack(hash: Option<HashType>) -> Result<Self, Error> {
+        Config::pack(hash).map(Self::wrap)
+    }
+
+    /// Start with a fresh store, removing any existing data
+    pub fn fresh(self, fresh: bool) -> Self {
+        self.opt("fresh", Opt::Bool(fresh))
+    }
+
+    /// Open the store in read-only mode
+    pub fn readonly(self, readonly: bool) -> Self {
+        self.opt("readonly", Opt::Bool(readonly))
+    }
+
+    /// Size of the index log, in number of entries
+    pub fn index_log_size(self, size: usize) -> Self {
+        self.opt("index-log-size", Opt::Int(size as i64))
+    }
+
+    /// Size of the LRU cache, in number of entries
+    pub fn lru_size(self, size: usize) -> Self {
+        self.opt("lru-size", Opt::Int(size as i64))
+    }
+}
+
+impl ConfigBuilder<IrminString, Pack> {
+    /// Create builder for Tezos context store
+    pub fn tezos() -> Result<Self, Error> {
+        Config::tezos().map(Self::wrap)
+    }
+}
+
+impl<T: Contents> ConfigBuilder<T, Git> {
+    /// Create builder for Irmin_git on-disk store
+    pub fn git() -> Result<Self, Error> {
+        Config::git().map(Self::wrap)
+    }
+
+    /// Create builder for Irmin_git in-memory store
+    pub fn git_mem() -> Result<Self, Error> {
+        Config::git_mem().map(Self::wrap)
+    }
+
+    /// Use a bare repository, without a checkout of the working tree
+    pub fn bare(self, bare: bool) -> Self {
+        self.opt("bare", Opt::Bool(bare))
+    }
+
+    /// Branch that `HEAD` points to
+    pub fn head(self, branch: impl Into<String>) -> Self {
+        self.opt("head", Opt::OptionString(Some(branch.into())))
+    }
+
+    /// Location of the `.git` directory, when it is not stored below the root
+    pub fn dot_git(self, path: impl AsRef<std::path::Path>) -> Self {
+        let path = path.as_ref().to_str().expect("Invalid path").to_string();
+        self.opt("dot-git", Opt::OptionString(Some(path)))
+    }
+}
+
+impl<T: Contents> ConfigBuilder<T, Fs> {
+    /// Create builder for Irmin_fs store
+    pub fn fs(hash: Option<HashType>) -> Result<Self, Error> {
+        Config::fs(hash).map(Self::wrap)
+    }
+}
+
+impl<T: Contents> ConfigBuilder<T, Mem> {
+    /// Create builder for Irmin_mem store
+    pub fn mem(hash: Option<HashType>) -> Result<Self, Error> {
+        Config::mem(hash).map(Self::wrap)
+    }
+}
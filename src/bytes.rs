@@ -0,0 +1,74 @@
+use crate::internal::*;
+
+/// Binary contents, stored as an Irmin string without requiring valid UTF-8
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+    /// Create new `Bytes`
+    pub fn new(b: impl Into<Vec<u8>>) -> Bytes {
+        Bytes(b.into())
+    }
+
+    /// Access the underlying bytes
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Convert to `Vec<u8>`
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl std::ops::Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(b: Vec<u8>) -> Bytes {
+        Bytes(b)
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(b: &[u8]) -> Bytes {
+        Bytes(b.to_vec())
+    }
+}
+
+impl From<IrminString> for Bytes {
+    fn from(s: IrminString) -> Bytes {
+        Bytes(s.into())
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(b: Bytes) -> Vec<u8> {
+        b.0
+    }
+}
+
+impl Contents for Bytes {
+    fn content_type() -> ContentType {
+        ContentType::String
+    }
+
+    fn to_value(&self) -> Result<Value, Error> {
+        Value::bytes(self)
+    }
+
+    fn from_value(v: &Value) -> Result<Self, Error> {
+        v.get_bytes().map(Bytes)
+    }
+}
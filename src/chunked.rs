@@ -0,0 +1,247 @@
+use crate::internal::*;
+
+use fastcdc::v2020::{FastCDC, AVERAGE_MAX, AVERAGE_MIN, MAXIMUM_MAX, MAXIMUM_MIN};
+use fastcdc::v2020::{MINIMUM_MAX, MINIMUM_MIN};
+
+fn chunk_name(i: usize) -> String {
+    format!("{i:08}")
+}
+
+fn not_chunked() -> Error {
+    Error::Type(format!(
+        "not a chunked value, chunk {} is missing",
+        chunk_name(0)
+    ))
+}
+
+fn io_error(e: Error) -> std::io::Error {
+    std::io::Error::other(e.to_string())
+}
+
+/// Stores large values as a tree of chunks under the value's path. Chunk boundaries are found
+/// using content-defined chunking (FastCDC), so after a small edit most chunks are unchanged
+/// and are shared with earlier versions of the value. Requires the `chunking` feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkedBlob {
+    min_size: u32,
+    avg_size: u32,
+    max_size: u32,
+}
+
+impl Default for ChunkedBlob {
+    fn default() -> ChunkedBlob {
+        ChunkedBlob::new()
+    }
+}
+
+impl ChunkedBlob {
+    /// Chunk values using the default sizes: 16KiB minimum, 64KiB average and 256KiB maximum
+    pub fn new() -> ChunkedBlob {
+        ChunkedBlob {
+            min_size: 16 * 1024,
+            avg_size: 64 * 1024,
+            max_size: 256 * 1024,
+        }
+    }
+
+    /// Chunk values using the given sizes. The minimum must be between 64B and 1MiB, the
+    /// average between 256B and 4MiB and the maximum between 1KiB and 16MiB
+    pub fn with_sizes(min_size: u32, avg_size: u32, max_size: u32) -> Result<ChunkedBlob, Error> {
+        if !(MINIMUM_MIN..=MINIMUM_MAX).contains(&min_size)
+            || !(AVERAGE_MIN..=AVERAGE_MAX).contains(&avg_size)
+            || !(MAXIMUM_MIN..=MAXIMUM_MAX).contains(&max_size)
+            || min_size > avg_size
+            || avg_size > max_size
+        {
+            return Err(Error::Config(format!(
+                "invalid chunk sizes: {min_size}, {avg_size}, {max_size}"
+            )));
+        }
+        Ok(ChunkedBlob {
+            min_size,
+            avg_size,
+            max_size,
+        })
+    }
+
+    fn chunks<'x>(&self, data: &'x [u8]) -> FastCDC<'x> {
+        FastCDC::new(data, self.min_size, self.avg_size, self.max_size)
+    }
+
+    /// Split `data` into a tree of chunks
+    pub fn to_tree<'a>(
+        &self,
+        repo: impl Into<RepoRef<'a, Bytes>>,
+        data: &[u8],
+    ) -> Result<Tree<'a, Bytes>, Error> {
+        let mut tree = Tree::new(repo)?;
+        let mut count = 0;
+        for chunk in self.chunks(data) {
+            let end = chunk.offset + chunk.length;
+            tree.add(
+                chunk_name(count),
+                &Bytes::from(&data[chunk.offset..end]),
+                None,
+            )?;
+            count += 1;
+        }
+
+        // Empty trees aren't stored, so empty values are stored as a single empty chunk
+        if count == 0 {
+            tree.add(chunk_name(0), &Bytes::default(), None)?;
+        }
+        Ok(tree)
+    }
+
+    /// Reassemble a value from a tree created by `ChunkedBlob::to_tree`, returns an error if
+    /// the tree doesn't contain a chunked value
+    pub fn from_tree(tree: &Tree<Bytes>) -> Result<Vec<u8>, Error> {
+        if !tree.mem(chunk_name(0))? {
+            return Err(not_chunked());
+        }
+        let mut dest = Vec::new();
+        let mut i = 0;
+        while let Some(chunk) = tree.find(chunk_name(i))? {
+            dest.extend(chunk.into_vec());
+            i += 1;
+        }
+        Ok(dest)
+    }
+
+    /// Split `data` into chunks and store them at `path`, creating a new commit
+    pub fn write(
+        &self,
+        store: &mut Store<Bytes>,
+        path: impl IntoPath,
+        data: &[u8],
+        info: Info,
+    ) -> Result<bool, Error> {
+        let tree = self.to_tree(&store.repo, data)?;
+        store.set_tree(path, &tree, info)
+    }
+
+    /// Read a chunked value from `path`, returns an error if the path holds a value that isn't
+    /// chunked
+    pub fn read(store: &Store<Bytes>, path: impl IntoPath) -> Result<Option<Vec<u8>>, Error> {
+        match store.find_tree(path)? {
+            Some(tree) => ChunkedBlob::from_tree(&tree).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Get a reader for the chunked value at `path`, chunks are loaded as they are read
+    pub fn reader<'a>(
+        store: &Store<'a, Bytes>,
+        path: impl IntoPath,
+    ) -> Result<Option<ChunkedReader<'a>>, Error> {
+        let tree = match store.find_tree(path)? {
+            Some(tree) => tree,
+            None => return Ok(None),
+        };
+        if !tree.mem(chunk_name(0))? {
+            return Err(not_chunked());
+        }
+        Ok(Some(ChunkedReader {
+            tree,
+            index: 0,
+            chunk: Vec::new(),
+            pos: 0,
+        }))
+    }
+
+    /// Get a writer that stores a chunked value at `path`, see `ChunkedWriter::finish`
+    pub fn writer<'a, 'b>(
+        &self,
+        store: &'b mut Store<'a, Bytes>,
+        path: impl IntoPath,
+    ) -> Result<ChunkedWriter<'a, 'b>, Error> {
+        let path = path
+            .into_path(&UntypedRepo::new(&store.repo))?
+            .to_path_buf()?;
+        let tree = Tree::new(&store.repo)?;
+        Ok(ChunkedWriter {
+            store,
+            path,
+            chunker: *self,
+            tree,
+            buf: Vec::new(),
+            count: 0,
+        })
+    }
+}
+
+/// Streaming reader for values stored using `ChunkedBlob`
+pub struct ChunkedReader<'a> {
+    tree: Tree<'a, Bytes>,
+    index: usize,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl<'a> std::io::Read for ChunkedReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            match self.tree.find(chunk_name(self.index)).map_err(io_error)? {
+                Some(chunk) => {
+                    self.chunk = chunk.into_vec();
+                    self.pos = 0;
+                    self.index += 1;
+                }
+                None => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Streaming writer for values stored using `ChunkedBlob`. Chunks are added to a tree as soon
+/// as their boundaries are known, nothing is stored until `ChunkedWriter::finish` is called
+pub struct ChunkedWriter<'a, 'b> {
+    store: &'b mut Store<'a, Bytes>,
+    path: PathBuf,
+    chunker: ChunkedBlob,
+    tree: Tree<'a, Bytes>,
+    buf: Vec<u8>,
+    count: usize,
+}
+
+impl<'a, 'b> ChunkedWriter<'a, 'b> {
+    fn add_chunk(&mut self) -> Result<(), Error> {
+        let n = match self.chunker.chunks(&self.buf).next() {
+            Some(chunk) => chunk.length,
+            None => self.buf.len(),
+        };
+        let chunk: Vec<u8> = self.buf.drain(..n).collect();
+        self.tree.add(chunk_name(self.count), &Bytes(chunk), None)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Store the remaining chunks, creating a new commit
+    pub fn finish(mut self, info: Info) -> Result<bool, Error> {
+        while !self.buf.is_empty() || self.count == 0 {
+            self.add_chunk()?;
+        }
+        self.store.set_tree(&self.path, &self.tree, info)
+    }
+}
+
+impl<'a, 'b> std::io::Write for ChunkedWriter<'a, 'b> {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.buf.extend_from_slice(data);
+
+        // The first chunk can't change once the buffer contains the maximum chunk size
+        while self.buf.len() >= self.chunker.max_size as usize {
+            self.add_chunk().map_err(io_error)?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
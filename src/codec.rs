@@ -0,0 +1,157 @@
+use crate::internal::*;
+
+/// Serialization format used by codec-based contents, see `Yaml`, `Toml`, `Cbor` and `MsgPack`
+pub trait Codec {
+    /// Tag stored in front of encoded values
+    const TAG: &'static str;
+
+    /// Encode a value, without the tag
+    fn encode<T: serde::Serialize>(x: &T) -> Result<Vec<u8>, Error>;
+
+    /// Decode a value encoded using `Codec::encode`, without the tag
+    fn decode<T: serde::de::DeserializeOwned>(b: &[u8]) -> Result<T, Error>;
+}
+
+fn header(tag: &str) -> Vec<u8> {
+    format!("\0{tag}:").into_bytes()
+}
+
+// Values are stored as `\0<tag>:<encoded value>`, the leading NUL can't start a YAML or TOML
+// document so text that happens to begin with a tag isn't mistaken for a tagged value.
+// Untagged values are decoded using the codec directly so existing files can be read, values
+// tagged with another format are rejected
+fn encode<C: Codec, T: serde::Serialize>(x: &T) -> Result<Value, Error> {
+    let mut b = header(C::TAG);
+    b.extend(C::encode(x)?);
+    Value::bytes(b)
+}
+
+fn decode<C: Codec, T: serde::de::DeserializeOwned>(v: &Value) -> Result<T, Error> {
+    let b = v.get_bytes()?;
+    if let Some(x) = b.strip_prefix(header(C::TAG).as_slice()) {
+        return C::decode(x);
+    }
+    if let Some(tag) = tag(&b) {
+        return Err(Error::Type(format!(
+            "expected {} contents but got {tag}",
+            C::TAG
+        )));
+    }
+    C::decode(&b)
+}
+
+fn tag(b: &[u8]) -> Option<&str> {
+    const TAGS: &[&str] = &["yaml", "toml", "cbor", "msgpack"];
+    TAGS.iter().find(|t| b.starts_with(&header(t))).copied()
+}
+
+macro_rules! codec_contents {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name<T>(pub T);
+
+        impl<T> $name<T> {
+            /// Get the decoded value
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        contents_wrapper!($name [T] $name<T>, 0, $name);
+
+        impl<T: serde::Serialize + serde::de::DeserializeOwned> Contents for $name<T> {
+            fn content_type() -> ContentType {
+                ContentType::String
+            }
+
+            fn to_value(&self) -> Result<Value, Error> {
+                encode::<Self, T>(&self.0)
+            }
+
+            fn from_value(v: &Value) -> Result<Self, Error> {
+                decode::<Self, T>(v).map($name)
+            }
+        }
+    };
+}
+
+codec_contents!(
+    /// TOML encoded contents, `T` must serialize to a table
+    Toml
+);
+
+impl<T> Codec for Toml<T> {
+    const TAG: &'static str = "toml";
+
+    fn encode<U: serde::Serialize>(x: &U) -> Result<Vec<u8>, Error> {
+        toml::to_string(x)
+            .map(String::into_bytes)
+            .map_err(|e| Error::Codec(e.to_string()))
+    }
+
+    fn decode<U: serde::de::DeserializeOwned>(b: &[u8]) -> Result<U, Error> {
+        toml::from_str(std::str::from_utf8(b)?).map_err(|e| Error::Codec(e.to_string()))
+    }
+}
+
+#[cfg(feature = "yaml")]
+codec_contents!(
+    /// YAML encoded contents
+    Yaml
+);
+
+#[cfg(feature = "yaml")]
+impl<T> Codec for Yaml<T> {
+    const TAG: &'static str = "yaml";
+
+    fn encode<U: serde::Serialize>(x: &U) -> Result<Vec<u8>, Error> {
+        serde_yaml::to_string(x)
+            .map(String::into_bytes)
+            .map_err(|e| Error::Codec(e.to_string()))
+    }
+
+    fn decode<U: serde::de::DeserializeOwned>(b: &[u8]) -> Result<U, Error> {
+        serde_yaml::from_slice(b).map_err(|e| Error::Codec(e.to_string()))
+    }
+}
+
+#[cfg(feature = "cbor")]
+codec_contents!(
+    /// CBOR encoded contents
+    Cbor
+);
+
+#[cfg(feature = "cbor")]
+impl<T> Codec for Cbor<T> {
+    const TAG: &'static str = "cbor";
+
+    fn encode<U: serde::Serialize>(x: &U) -> Result<Vec<u8>, Error> {
+        let mut b = Vec::new();
+        ciborium::into_writer(x, &mut b).map_err(|e| Error::Codec(e.to_string()))?;
+        Ok(b)
+    }
+
+    fn decode<U: serde::de::DeserializeOwned>(b: &[u8]) -> Result<U, Error> {
+        ciborium::from_reader(b).map_err(|e| Error::Codec(e.to_string()))
+    }
+}
+
+#[cfg(feature = "msgpack")]
+codec_contents!(
+    /// MessagePack encoded contents, structs are encoded as maps
+    MsgPack
+);
+
+#[cfg(feature = "msgpack")]
+impl<T> Codec for MsgPack<T> {
+    const TAG: &'static str = "msgpack";
+
+    fn encode<U: serde::Serialize>(x: &U) -> Result<Vec<u8>, Error> {
+        rmp_serde::to_vec_named(x).map_err(|e| Error::Codec(e.to_string()))
+    }
+
+    fn decode<U: serde::de::DeserializeOwned>(b: &[u8]) -> Result<U, Error> {
+        rmp_serde::from_slice(b).map_err(|e| Error::Codec(e.to_string()))
+    }
+}
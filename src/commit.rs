@@ -1,31 +1,36 @@
 use crate::internal::*;
 
-/// Wrapper around Irmin commits
-pub struct Commit<'a> {
+/// Wrapper around Irmin commits, `T` is the content type of the repo the commit belongs to
+pub struct Commit<'a, T: Contents> {
     pub ptr: *mut IrminCommit,
     pub(crate) repo: UntypedRepo<'a>,
+    pub(crate) _t: std::marker::PhantomData<T>,
 }
 
-impl<'a> Drop for Commit<'a> {
+impl<'a, T: Contents> Drop for Commit<'a, T> {
     fn drop(&mut self) {
         unsafe { irmin_commit_free(self.ptr) }
     }
 }
 
-impl<'a> PartialEq for Commit<'a> {
-    fn eq(&self, other: &Commit<'a>) -> bool {
+impl<'a, T: Contents> PartialEq for Commit<'a, T> {
+    fn eq(&self, other: &Commit<'a, T>) -> bool {
         unsafe { irmin_commit_equal(self.repo.ptr, self.ptr, other.ptr) }
     }
 }
 
-impl<'a> Commit<'a> {
+impl<'a, T: Contents> Commit<'a, T> {
     /// Create a new commit
-    pub fn new<T: Contents>(
-        repo: &'a Repo<T>,
-        parents: impl AsRef<[&'a Commit<'a>]>,
+    pub fn new<'b>(
+        repo: impl Into<RepoRef<'a, T>>,
+        parents: impl AsRef<[&'b Commit<'b, T>]>,
         tree: &Tree<T>,
         info: Info,
-    ) -> Result<Commit<'a>, Error> {
+    ) -> Result<Commit<'a, T>, Error>
+    where
+        T: 'b,
+    {
+        let repo = repo.into();
         let parents: Vec<_> = parents.as_ref().iter().map(|x| x.ptr).collect();
         let ptr = unsafe {
             irmin_commit_new(
@@ -39,25 +44,28 @@ impl<'a> Commit<'a> {
         check!(repo.ptr, ptr);
         Ok(Commit {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
+            _t: std::marker::PhantomData,
         })
     }
 
     /// Find the commit associated with the given hash
-    pub fn of_hash<T: Contents>(
-        repo: &'a Repo<T>,
+    pub fn of_hash(
+        repo: impl Into<RepoRef<'a, T>>,
         hash: &Hash,
-    ) -> Result<Option<Commit<'a>>, Error> {
+    ) -> Result<Option<Commit<'a, T>>, Error> {
+        let repo = repo.into();
         let ptr = unsafe { irmin_commit_of_hash(repo.ptr, hash.ptr) };
         check_opt!(repo.ptr, ptr);
         Ok(Some(Commit {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
+            _t: std::marker::PhantomData,
         }))
     }
 
     /// Get the hash associated with a commit
-    pub fn hash(&self) -> Result<Hash, Error> {
+    pub fn hash(&self) -> Result<Hash<'a>, Error> {
         let ptr = unsafe { irmin_commit_hash(self.repo.ptr, self.ptr) };
         check!(self.repo.ptr, ptr);
         Ok(Hash {
@@ -67,20 +75,22 @@ impl<'a> Commit<'a> {
     }
 
     /// Find the commit associated with the given key
-    pub fn of_key<T: Contents>(
-        repo: &'a Repo<T>,
+    pub fn of_key(
+        repo: impl Into<RepoRef<'a, T>>,
         key: &CommitKey,
-    ) -> Result<Option<Commit<'a>>, Error> {
+    ) -> Result<Option<Commit<'a, T>>, Error> {
+        let repo = repo.into();
         let ptr = unsafe { irmin_commit_of_key(repo.ptr, key.ptr) };
         check_opt!(repo.ptr, ptr);
         Ok(Some(Commit {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
+            _t: std::marker::PhantomData,
         }))
     }
 
     /// Get the key associated with a commit
-    pub fn key(&self) -> Result<CommitKey, Error> {
+    pub fn key(&self) -> Result<CommitKey<'a>, Error> {
         let ptr = unsafe { irmin_commit_key(self.repo.ptr, self.ptr) };
         check!(self.repo.ptr, ptr);
         Ok(CommitKey {
@@ -90,7 +100,7 @@ impl<'a> Commit<'a> {
     }
 
     /// Get commit info
-    pub fn info(&self) -> Result<Info, Error> {
+    pub fn info(&self) -> Result<Info<'a>, Error> {
         let ptr = unsafe { irmin_commit_info(self.repo.ptr, self.ptr) };
         check!(self.repo.ptr, ptr);
         Ok(Info {
@@ -99,7 +109,8 @@ impl<'a> Commit<'a> {
         })
     }
 
-    pub fn tree<T: Contents>(&self) -> Result<Tree<T>, Error> {
+    /// Get the tree associated with a commit
+    pub fn tree(&self) -> Result<Tree<'a, T>, Error> {
         let ptr = unsafe { irmin_commit_tree(self.repo.ptr, self.ptr) };
         check!(self.repo.ptr, ptr);
         Ok(Tree {
@@ -110,7 +121,7 @@ impl<'a> Commit<'a> {
     }
 
     /// Get commit parents
-    pub fn parents(&self) -> Result<Vec<Commit>, Error> {
+    pub fn parents(&self) -> Result<Vec<Commit<'a, T>>, Error> {
         let p = unsafe { irmin_commit_parents(self.repo.ptr, self.ptr) };
         check!(self.repo.ptr, p);
         let len = unsafe { irmin_commit_array_length(self.repo.ptr, p) };
@@ -123,6 +134,7 @@ impl<'a> Commit<'a> {
             dest.push(Commit {
                 ptr: c,
                 repo: self.repo.clone(),
+                _t: std::marker::PhantomData,
             })
         }
 
@@ -130,4 +142,15 @@ impl<'a> Commit<'a> {
 
         Ok(dest)
     }
+
+    /// Change the content type of a commit, this is unchecked so reading the tree of a commit
+    /// using the wrong content type will fail at runtime
+    pub fn cast<U: Contents>(self) -> Commit<'a, U> {
+        let commit = std::mem::ManuallyDrop::new(self);
+        Commit {
+            ptr: commit.ptr,
+            repo: commit.repo.clone(),
+            _t: std::marker::PhantomData,
+        }
+    }
 }
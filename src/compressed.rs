@@ -0,0 +1,108 @@
+use crate::internal::*;
+
+const ZSTD: &[u8] = b"\0zstd";
+
+const RAW: &[u8] = b"\0raw";
+
+const LEVEL: i32 = 3;
+
+// Largest value that will be decompressed, larger or corrupt values fail to decode instead of
+// being expanded in memory
+const MAX_DECOMPRESSED_SIZE: u64 = 256 << 20;
+
+// Decompress a value written by `to_value_in`, the size is read from the zstd frame header and
+// checked against `MAX_DECOMPRESSED_SIZE` before allocating
+fn decompress(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let size = match zstd::zstd_safe::get_frame_content_size(data) {
+        Ok(Some(size)) => size,
+        _ => {
+            return Err(Error::Codec(String::from(
+                "invalid zstd frame: missing content size",
+            )))
+        }
+    };
+    if size > MAX_DECOMPRESSED_SIZE {
+        return Err(Error::Codec(format!(
+            "decompressed value is too large: {size} bytes"
+        )));
+    }
+    zstd::bulk::decompress(data, size as usize).map_err(|e| Error::Codec(e.to_string()))
+}
+
+/// Contents that are compressed using zstd when the encoded value is at least `MIN_SIZE` bytes.
+/// Values are stored with a small header, values without a header are read as-is so existing
+/// uncompressed values can still be decoded. String contents are stored as raw bytes and JSON
+/// contents as JSON text
+///
+/// Compressed values are always stored using the string content type, whatever the content
+/// type of `T`. Existing stores with string contents can be opened as `Compressed<T>`, but
+/// stores created with `Json` or `JsonValue` contents can't because the content type is part
+/// of the store's configuration, for example `Compressed<JsonValue>` can't open a pack store
+/// that was created for `JsonValue`
+///
+/// Decoding fails with `Error::Codec` for values that would decompress to more than 256MiB
+#[derive(Debug, Clone, PartialEq)]
+pub struct Compressed<T, const MIN_SIZE: usize = 4096>(pub T);
+
+impl<T, const MIN_SIZE: usize> Compressed<T, MIN_SIZE> {
+    /// Get the decompressed value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+contents_wrapper!(Compressed [T, const MIN_SIZE: usize] Compressed<T, MIN_SIZE>, 0, Compressed);
+
+impl<T: Contents, const MIN_SIZE: usize> Contents for Compressed<T, MIN_SIZE> {
+    fn content_type() -> ContentType {
+        ContentType::String
+    }
+
+    fn to_value(&self) -> Result<Value, Error> {
+        self.to_value_in(&ContentsContext::default())
+    }
+
+    fn from_value(v: &Value) -> Result<Self, Error> {
+        Self::from_value_in(v, &ContentsContext::default())
+    }
+
+    fn to_value_in(&self, ctx: &ContentsContext) -> Result<Value, Error> {
+        let v = self.0.to_value_in(ctx)?;
+        let data = match T::content_type() {
+            ContentType::String => v.get_bytes()?,
+            ContentType::Json | ContentType::JsonValue => v.to_json()?.as_slice().to_vec(),
+        };
+
+        if data.len() >= MIN_SIZE {
+            let compressed = zstd::bulk::compress(&data, LEVEL)?;
+            if compressed.len() < data.len() {
+                let mut b = ZSTD.to_vec();
+                b.extend(compressed);
+                return Value::bytes(b);
+            }
+        }
+
+        let mut b = RAW.to_vec();
+        b.extend(data);
+        Value::bytes(b)
+    }
+
+    fn from_value_in(v: &Value, ctx: &ContentsContext) -> Result<Self, Error> {
+        let b = v.get_bytes()?;
+        let data = if let Some(x) = b.strip_prefix(ZSTD) {
+            decompress(x)?
+        } else if let Some(x) = b.strip_prefix(RAW) {
+            x.to_vec()
+        } else {
+            b
+        };
+
+        let v = match T::content_type() {
+            ContentType::String => Value::bytes(data)?,
+            ContentType::Json | ContentType::JsonValue => {
+                Value::of_json(T::ty()?, std::str::from_utf8(&data)?)?
+            }
+        };
+        T::from_value_in(&v, ctx).map(Compressed)
+    }
+}
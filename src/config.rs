@@ -3,7 +3,9 @@ use crate::internal::*;
 /// Wrapper around Irmin.config
 pub struct Config<T: Contents> {
     pub ptr: *mut IrminConfig,
-    _t: std::marker::PhantomData<T>,
+    pub(crate) hash_type: HashType,
+    pub(crate) context: ContentsContext,
+    pub(crate) _t: std::marker::PhantomData<T>,
 }
 
 impl<T: Contents> Drop for Config<T> {
@@ -13,12 +15,22 @@ impl<T: Contents> Drop for Config<T> {
 }
 
 /// Builtin content types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ContentType {
     String,
     Json,
     JsonValue,
 }
 
+/// Per-repo state passed to `Contents::to_value_in` and `Contents::from_value_in`, it is set
+/// on the `Config` used to create the repo
+#[derive(Clone, Default)]
+pub struct ContentsContext {
+    #[allow(dead_code)]
+    pub(crate) key_provider: Option<std::sync::Arc<dyn std::any::Any + Send + Sync>>,
+}
+
 const CONTENTS_STRING: &str = "string\0";
 
 const CONTENTS_JSON: &str = "json\0";
@@ -34,6 +46,17 @@ where
     fn to_value(&self) -> Result<Value, Error>;
     fn from_value(v: &Value) -> Result<Self, Error>;
 
+    /// Convert to a value using the repo's `ContentsContext`, contents that need per-repo
+    /// state such as `Encrypted` override this. Defaults to `Contents::to_value`
+    fn to_value_in(&self, _ctx: &ContentsContext) -> Result<Value, Error> {
+        self.to_value()
+    }
+
+    /// Convert from a value using the repo's `ContentsContext`, see `Contents::to_value_in`
+    fn from_value_in(v: &Value, _ctx: &ContentsContext) -> Result<Self, Error> {
+        Self::from_value(v)
+    }
+
     fn of_hash<'a>(repo: &'a Repo<Self>, hash: &Hash) -> Option<Self> {
         let ptr = unsafe { irmin_contents_of_hash(repo.ptr, hash.ptr) };
         if ptr.is_null() {
@@ -47,14 +70,30 @@ where
             ptr: ptr as *mut _,
             ty,
         };
-        match Self::from_value(&v) {
+        match Self::from_value_in(&v, &repo.config.context) {
             Ok(x) => Some(x),
             Err(_) => None,
         }
     }
 
+    fn of_key(repo: &Repo<Self>, key: &ContentsKey) -> Option<Self> {
+        let ptr = unsafe { irmin_contents_of_key(repo.ptr, key.ptr) };
+        if ptr.is_null() {
+            return None;
+        }
+        let ty = match Type::contents(repo) {
+            Ok(t) => t,
+            Err(_) => return None,
+        };
+        let v = Value {
+            ptr: ptr as *mut _,
+            ty,
+        };
+        Self::from_value_in(&v, &repo.config.context).ok()
+    }
+
     fn hash<'a>(&self, repo: &'a Repo<Self>) -> Result<Hash<'a>, Error> {
-        let v = self.to_value()?;
+        let v = self.to_value_in(&repo.config.context)?;
 
         let ptr = unsafe { irmin_contents_hash(repo.ptr, v.ptr as *mut _) };
         check!(repo.ptr, ptr);
@@ -65,15 +104,20 @@ where
     }
 
     fn ty() -> Result<Type, Error> {
-        match Self::content_type() {
+        Self::content_type().ty()
+    }
+}
+
+impl ContentType {
+    /// Irmin type used to encode contents
+    pub fn ty(&self) -> Result<Type, Error> {
+        match self {
             ContentType::String => Type::string(),
             ContentType::Json => Type::json(),
             ContentType::JsonValue => Type::json_value(),
         }
     }
-}
 
-impl ContentType {
     fn ptr(c: Option<ContentType>) -> *const u8 {
         match c {
             Some(ContentType::String) => CONTENTS_STRING.as_ptr(),
@@ -85,6 +129,19 @@ impl ContentType {
 }
 
 /// Available hash types
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    std::hash::Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum HashType {
     Blake2b,
     Blake2s,
@@ -121,6 +178,58 @@ impl HashType {
             None => std::ptr::null(),
         }
     }
+
+    /// Name of the hash function, as used by libirmin
+    pub fn name(&self) -> &'static str {
+        let s = match self {
+            HashType::Sha1 => HASH_SHA1,
+            HashType::Sha224 => HASH_SHA224,
+            HashType::Sha256 => HASH_SHA256,
+            HashType::Sha384 => HASH_SHA384,
+            HashType::Sha512 => HASH_SHA512,
+            HashType::Blake2b => HASH_BLAKE2B,
+            HashType::Blake2s => HASH_BLAKE2S,
+            HashType::Rmd160 => HASH_RMD160,
+        };
+        &s[..s.len() - 1]
+    }
+
+    /// Size of a hash in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            HashType::Sha1 | HashType::Rmd160 => 20,
+            HashType::Sha224 => 28,
+            HashType::Sha256 | HashType::Blake2s => 32,
+            HashType::Sha384 => 48,
+            HashType::Sha512 | HashType::Blake2b => 64,
+        }
+    }
+}
+
+impl std::fmt::Display for HashType {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for HashType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<HashType, Error> {
+        [
+            HashType::Blake2b,
+            HashType::Blake2s,
+            HashType::Rmd160,
+            HashType::Sha1,
+            HashType::Sha224,
+            HashType::Sha256,
+            HashType::Sha384,
+            HashType::Sha512,
+        ]
+        .into_iter()
+        .find(|h| h.name() == s)
+        .ok_or_else(|| Error::Parse(format!("invalid hash type: {s}")))
+    }
 }
 
 impl Contents for IrminString {
@@ -129,7 +238,7 @@ impl Contents for IrminString {
     }
 
     fn to_value(&self) -> Result<Value, Error> {
-        Value::string(self)
+        Value::bytes(self.as_slice())
     }
 
     fn from_value(v: &Value) -> Result<Self, Error> {
@@ -147,7 +256,7 @@ impl Contents for String {
     }
 
     fn from_value(v: &Value) -> Result<Self, Error> {
-        v.get_string().map(|x| x.into())
+        v.get_string()?.to_str().map(String::from)
     }
 }
 
@@ -161,7 +270,7 @@ impl Contents for Vec<u8> {
     }
 
     fn from_value(v: &Value) -> Result<Self, Error> {
-        v.get_string().map(|x| x.into())
+        v.get_bytes()
     }
 }
 
@@ -178,7 +287,7 @@ impl Contents for serde_json::Value {
 
     fn from_value(v: &Value) -> Result<Self, Error> {
         let s = v.to_string()?;
-        serde_json::from_str(s.as_ref()).map_err(Error::from)
+        serde_json::from_slice(s.as_slice()).map_err(Error::from)
     }
 }
 
@@ -196,13 +305,26 @@ impl Contents for serde_json::Map<String, serde_json::Value> {
     fn from_value(v: &Value) -> Result<Self, Error> {
         let s = v.to_string()?;
 
-        serde_json::from_str(s.as_ref()).map_err(Error::from)
+        serde_json::from_slice(s.as_slice()).map_err(Error::from)
     }
 }
 
 impl Config<IrminString> {
     /// Create configuration for Tezos context store
     pub fn tezos() -> Result<Config<IrminString>, Error> {
+        Config::tezos_contents()
+    }
+}
+
+impl<T: Contents> Config<T> {
+    // Create configuration for Tezos context store with any contents using the string content
+    // type, used by `ConfigFile` where the contents type is chosen by the caller
+    pub(crate) fn tezos_contents() -> Result<Config<T>, Error> {
+        if T::content_type() != ContentType::String {
+            return Err(Error::Config(String::from(
+                "the tezos backend requires string contents",
+            )));
+        }
         unsafe {
             let ptr = irmin_config_tezos();
             if ptr.is_null() {
@@ -210,16 +332,17 @@ impl Config<IrminString> {
             }
             Ok(Config {
                 ptr,
+                hash_type: HashType::Blake2b,
+                context: ContentsContext::default(),
                 _t: std::marker::PhantomData,
             })
         }
     }
-}
 
-impl<T: Contents> Config<T> {
     /// Create configuration for Irmin_pack store
     pub fn pack(hash: Option<HashType>) -> Result<Config<T>, Error> {
         unsafe {
+            let hash_type = hash.unwrap_or(HashType::Blake2b);
             let hash = HashType::ptr(hash);
             let contents = ContentType::ptr(Some(T::content_type()));
             let ptr = irmin_config_pack(hash as *mut _, contents as *mut _);
@@ -228,6 +351,8 @@ impl<T: Contents> Config<T> {
             }
             Ok(Config {
                 ptr,
+                hash_type,
+                context: ContentsContext::default(),
                 _t: std::marker::PhantomData,
             })
         }
@@ -236,6 +361,7 @@ impl<T: Contents> Config<T> {
     /// Create configuration for Irmin_mem store
     pub fn mem(hash: Option<HashType>) -> Result<Config<T>, Error> {
         unsafe {
+            let hash_type = hash.unwrap_or(HashType::Blake2b);
             let hash = HashType::ptr(hash);
             let contents = ContentType::ptr(Some(T::content_type()));
             let ptr = irmin_config_mem(hash as *mut _, contents as *mut _);
@@ -244,6 +370,8 @@ impl<T: Contents> Config<T> {
             }
             Ok(Config {
                 ptr,
+                hash_type,
+                context: ContentsContext::default(),
                 _t: std::marker::PhantomData,
             })
         }
@@ -252,6 +380,7 @@ impl<T: Contents> Config<T> {
     /// Create configuration for Irmin_fs store
     pub fn fs(hash: Option<HashType>) -> Result<Config<T>, Error> {
         unsafe {
+            let hash_type = hash.unwrap_or(HashType::Blake2b);
             let hash = HashType::ptr(hash);
             let contents = ContentType::ptr(Some(T::content_type()));
             let ptr = irmin_config_fs(hash as *mut _, contents as *mut _);
@@ -260,6 +389,8 @@ impl<T: Contents> Config<T> {
             }
             Ok(Config {
                 ptr,
+                hash_type,
+                context: ContentsContext::default(),
                 _t: std::marker::PhantomData,
             })
         }
@@ -275,6 +406,8 @@ impl<T: Contents> Config<T> {
             }
             Ok(Config {
                 ptr,
+                hash_type: HashType::Sha1,
+                context: ContentsContext::default(),
                 _t: std::marker::PhantomData,
             })
         }
@@ -290,6 +423,8 @@ impl<T: Contents> Config<T> {
             }
             Ok(Config {
                 ptr,
+                hash_type: HashType::Sha1,
+                context: ContentsContext::default(),
                 _t: std::marker::PhantomData,
             })
         }
@@ -301,6 +436,11 @@ impl<T: Contents> Config<T> {
         unsafe { irmin_config_set(self.ptr, key.as_ptr() as *mut _, ty.ptr, v.ptr) }
     }
 
+    /// Hash type used by the store
+    pub fn hash_type(&self) -> HashType {
+        self.hash_type
+    }
+
     /// Set root key
     pub fn set_root(&mut self, root: impl AsRef<std::path::Path>) -> bool {
         let v = cstring(root.as_ref().to_str().expect("Invalid path"));
@@ -0,0 +1,281 @@
+use crate::internal::*;
+
+use crate::builder::{Fs, Git, Mem, Pack};
+
+/// Storage backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    Git,
+    GitMem,
+    Pack,
+    Fs,
+    Mem,
+    Tezos,
+}
+
+/// Store configuration loaded from a file or the environment
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub backend: Backend,
+    pub root: Option<std::path::PathBuf>,
+    pub hash: Option<HashType>,
+    pub contents: Option<ContentType>,
+
+    // Irmin_pack and Tezos options
+    pub fresh: Option<bool>,
+    pub readonly: Option<bool>,
+    pub index_log_size: Option<usize>,
+    pub lru_size: Option<usize>,
+
+    // Irmin_git options
+    pub bare: Option<bool>,
+    pub head: Option<String>,
+    pub dot_git: Option<std::path::PathBuf>,
+}
+
+type EnvError = serde::de::value::Error;
+
+// Environment variable value, parsed according to the type of the field it's deserialized into
+struct EnvValue {
+    key: String,
+    value: String,
+}
+
+impl<'de> serde::de::IntoDeserializer<'de, EnvError> for EnvValue {
+    type Deserializer = EnvValue;
+
+    fn into_deserializer(self) -> EnvValue {
+        self
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for EnvValue {
+    type Error = EnvError;
+
+    fn deserialize_any<V: serde::de::Visitor<'de>>(self, v: V) -> Result<V::Value, EnvError> {
+        v.visit_string(self.value)
+    }
+
+    fn deserialize_bool<V: serde::de::Visitor<'de>>(self, v: V) -> Result<V::Value, EnvError> {
+        match self.value.as_str() {
+            "true" | "1" => v.visit_bool(true),
+            "false" | "0" => v.visit_bool(false),
+            _ => Err(serde::de::Error::custom(format!(
+                "expected boolean: {}={}",
+                self.key, self.value
+            ))),
+        }
+    }
+
+    fn deserialize_u64<V: serde::de::Visitor<'de>>(self, v: V) -> Result<V::Value, EnvError> {
+        match self.value.parse::<u64>() {
+            Ok(i) => v.visit_u64(i),
+            Err(_) => Err(serde::de::Error::custom(format!(
+                "expected integer: {}={}",
+                self.key, self.value
+            ))),
+        }
+    }
+
+    fn deserialize_option<V: serde::de::Visitor<'de>>(self, v: V) -> Result<V::Value, EnvError> {
+        v.visit_some(self)
+    }
+
+    fn deserialize_enum<V: serde::de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        v: V,
+    ) -> Result<V::Value, EnvError> {
+        use serde::de::IntoDeserializer;
+        let d: serde::de::value::StringDeserializer<EnvError> = self.value.into_deserializer();
+        d.deserialize_enum(name, variants, v)
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct newtype_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl ConfigFile {
+    /// Create a configuration for the given backend with every option unset
+    pub fn new(backend: Backend) -> ConfigFile {
+        ConfigFile {
+            backend,
+            root: None,
+            hash: None,
+            contents: None,
+            fresh: None,
+            readonly: None,
+            index_log_size: None,
+            lru_size: None,
+            bare: None,
+            head: None,
+            dot_git: None,
+        }
+    }
+
+    /// Parse a TOML or JSON configuration file, the format is determined by the file extension
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<ConfigFile, Error> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("toml") => toml::from_str(&s).map_err(|e| Error::Config(e.to_string())),
+            Some("json") => serde_json::from_str(&s).map_err(Error::from),
+            _ => Err(Error::Config(format!(
+                "unknown configuration format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Read configuration from environment variables, for example with the prefix `IRMIN_` the
+    /// backend is read from `IRMIN_BACKEND` and the LRU size from `IRMIN_LRU_SIZE`
+    pub fn from_env(prefix: impl AsRef<str>) -> Result<ConfigFile, Error> {
+        use serde::Deserialize;
+        let prefix = prefix.as_ref();
+        let vars = std::env::vars().filter_map(|(k, value)| {
+            let key = k.strip_prefix(prefix)?.to_lowercase();
+            Some((key, EnvValue { key: k, value }))
+        });
+        let d = serde::de::value::MapDeserializer::new(vars);
+        ConfigFile::deserialize(d).map_err(|e| Error::Config(e.to_string()))
+    }
+
+    fn check(backend: &str, keys: &[(&str, bool)]) -> Result<(), Error> {
+        for (key, is_set) in keys {
+            if *is_set {
+                return Err(Error::Config(format!(
+                    "{key} is not supported by the {backend} backend"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_pack(&self, backend: &str) -> Result<(), Error> {
+        Self::check(
+            backend,
+            &[
+                ("fresh", self.fresh.is_some()),
+                ("readonly", self.readonly.is_some()),
+                ("index_log_size", self.index_log_size.is_some()),
+                ("lru_size", self.lru_size.is_some()),
+            ],
+        )
+    }
+
+    fn check_git(&self, backend: &str) -> Result<(), Error> {
+        Self::check(
+            backend,
+            &[
+                ("bare", self.bare.is_some()),
+                ("head", self.head.is_some()),
+                ("dot_git", self.dot_git.is_some()),
+            ],
+        )
+    }
+
+    fn pack<T: Contents>(&self, builder: ConfigBuilder<T, Pack>) -> ConfigBuilder<T, Pack> {
+        let mut builder = builder;
+        if let Some(x) = self.fresh {
+            builder = builder.fresh(x);
+        }
+        if let Some(x) = self.readonly {
+            builder = builder.readonly(x);
+        }
+        if let Some(x) = self.index_log_size {
+            builder = builder.index_log_size(x);
+        }
+        if let Some(x) = self.lru_size {
+            builder = builder.lru_size(x);
+        }
+        builder
+    }
+
+    fn git<T: Contents>(&self, builder: ConfigBuilder<T, Git>) -> ConfigBuilder<T, Git> {
+        let mut builder = builder;
+        if let Some(x) = self.bare {
+            builder = builder.bare(x);
+        }
+        if let Some(x) = &self.head {
+            builder = builder.head(x);
+        }
+        if let Some(x) = &self.dot_git {
+            builder = builder.dot_git(x);
+        }
+        builder
+    }
+
+    /// Build a `Config`, the `contents` field must match the content type of `T` when it is set
+    pub fn config<T: Contents>(&self) -> Result<Config<T>, Error> {
+        if let Some(contents) = self.contents {
+            if contents != T::content_type() {
+                return Err(Error::Config(format!(
+                    "expected {:?} contents but the configuration specifies {:?}",
+                    T::content_type(),
+                    contents
+                )));
+            }
+        }
+
+        let has_hash = matches!(self.backend, Backend::Pack | Backend::Fs | Backend::Mem);
+        if self.hash.is_some() && !has_hash {
+            return Err(Error::Config(format!(
+                "hash is not supported by the {:?} backend",
+                self.backend
+            )));
+        }
+
+        match self.backend {
+            Backend::Pack => {
+                self.check_git("pack")?;
+                self.finish(self.pack(ConfigBuilder::pack(self.hash)?))
+            }
+            Backend::Tezos => {
+                self.check_git("tezos")?;
+                self.finish(self.pack(ConfigBuilder::wrap(Config::tezos_contents()?)))
+            }
+            Backend::Git => {
+                self.check_pack("git")?;
+                self.finish(self.git(ConfigBuilder::git()?))
+            }
+            Backend::GitMem => {
+                self.check_pack("git-mem")?;
+                self.finish(self.git(ConfigBuilder::git_mem()?))
+            }
+            Backend::Fs => {
+                self.check_pack("fs")?;
+                self.check_git("fs")?;
+                self.finish(ConfigBuilder::<T, Fs>::fs(self.hash)?)
+            }
+            Backend::Mem => {
+                self.check_pack("mem")?;
+                self.check_git("mem")?;
+                self.finish(ConfigBuilder::<T, Mem>::mem(self.hash)?)
+            }
+        }
+    }
+
+    fn finish<T: Contents, B>(&self, builder: ConfigBuilder<T, B>) -> Result<Config<T>, Error> {
+        match &self.root {
+            Some(root) => builder.root(root).build(),
+            None => builder.build(),
+        }
+    }
+}
+
+impl<T: Contents> Config<T> {
+    /// Load configuration from a TOML or JSON file, see `ConfigFile`
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Config<T>, Error> {
+        ConfigFile::load(path)?.config()
+    }
+
+    /// Load configuration from environment variables, see `ConfigFile::from_env`
+    pub fn from_env(prefix: impl AsRef<str>) -> Result<Config<T>, Error> {
+        ConfigFile::from_env(prefix)?.config()
+    }
+}
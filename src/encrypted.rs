@@ -0,0 +1,166 @@
+use crate::internal::*;
+
+use blake2::digest::consts::{U24, U32};
+use blake2::digest::Mac;
+use blake2::Blake2bMac;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+const TAG: &[u8] = b"enc1:";
+
+const NONCE_LEN: usize = 24;
+
+const NONCE_PERSONAL: &[u8] = b"irmin-rs-nonce";
+
+const CIPHER_KEY_PERSONAL: &[u8] = b"irmin-rs-ckey";
+
+const NONCE_KEY_PERSONAL: &[u8] = b"irmin-rs-nkey";
+
+/// Supplies keys for `Encrypted` contents, see `Config::set_key_provider`. Each key has an ID
+/// that is stored with the ciphertext, so values encrypted with an older key can still be read
+/// after rotating to a new one as long as `KeyProvider::key` can still find it
+pub trait KeyProvider: Send + Sync + 'static {
+    /// ID and key used to encrypt new values
+    fn current_key(&self) -> Result<(u32, [u8; 32]), Error>;
+
+    /// Find the key with the given ID
+    fn key(&self, id: u32) -> Result<Option<[u8; 32]>, Error>;
+}
+
+impl<T: Contents> Config<T> {
+    /// Set the key provider used by `Encrypted` contents in repos created using this config
+    pub fn set_key_provider(&mut self, keys: impl KeyProvider) {
+        self.context.key_provider = Some(std::sync::Arc::new(keys));
+    }
+}
+
+impl ContentsContext {
+    fn keys<K: KeyProvider>(&self) -> Result<&K, Error> {
+        match self.key_provider.as_deref().and_then(|k| k.downcast_ref()) {
+            Some(k) => Ok(k),
+            None => Err(Error::Crypto(String::from(
+                "no key provider, see Config::set_key_provider",
+            ))),
+        }
+    }
+}
+
+// Derive a subkey from a key provider key, each primitive gets its own subkey
+fn subkey(key: &[u8; 32], personal: &[u8]) -> Result<Key, Error> {
+    let mac = Blake2bMac::<U32>::new_with_salt_and_personal(key, &[], personal)
+        .map_err(|e| Error::Crypto(e.to_string()))?;
+    Ok(mac.finalize().into_bytes())
+}
+
+// The nonce is a keyed hash of the header and plaintext, so encryption is deterministic
+fn nonce(nonce_key: &Key, header: &[u8], plaintext: &[u8]) -> Result<XNonce, Error> {
+    let mut mac = Blake2bMac::<U24>::new_with_salt_and_personal(nonce_key, &[], NONCE_PERSONAL)
+        .map_err(|e| Error::Crypto(e.to_string()))?;
+    mac.update(header);
+    mac.update(plaintext);
+    Ok(mac.finalize().into_bytes())
+}
+
+/// Contents that are encrypted using XChaCha20-Poly1305 before being stored, `T` is encoded
+/// using Irmin's binary encoding. Values are stored as
+/// `enc1:<key id: 4 bytes, big endian><nonce: 24 bytes><ciphertext>`
+///
+/// The cipher key and the nonce key are separate subkeys derived from the key provider's key
+/// using BLAKE2b. Encryption is deterministic: the nonce is a keyed hash of the plaintext, so
+/// equal values encrypted with the same key are stored once. This reveals which stored values
+/// are equal to anyone who can read the store. Values can only be converted through a repo
+/// whose config has a key provider, see `Config::set_key_provider`
+pub struct Encrypted<T, K: KeyProvider> {
+    value: T,
+    _k: std::marker::PhantomData<K>,
+}
+
+impl<T, K: KeyProvider> Encrypted<T, K> {
+    /// Wrap a value
+    pub fn new(value: T) -> Encrypted<T, K> {
+        Encrypted {
+            value,
+            _k: std::marker::PhantomData,
+        }
+    }
+
+    /// Get the decrypted value
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+contents_wrapper!(
+    Encrypted [T, K: KeyProvider] Encrypted<T, K>,
+    value,
+    Encrypted::new,
+    Clone,
+    PartialEq,
+    Debug
+);
+
+impl<T: Contents, K: KeyProvider> Contents for Encrypted<T, K> {
+    fn content_type() -> ContentType {
+        ContentType::String
+    }
+
+    fn to_value(&self) -> Result<Value, Error> {
+        self.to_value_in(&ContentsContext::default())
+    }
+
+    fn from_value(v: &Value) -> Result<Self, Error> {
+        Self::from_value_in(v, &ContentsContext::default())
+    }
+
+    fn to_value_in(&self, ctx: &ContentsContext) -> Result<Value, Error> {
+        let plaintext = self.value.to_value_in(ctx)?.to_bin()?;
+        let (id, key) = ctx.keys::<K>()?.current_key()?;
+        let header: Vec<u8> = TAG.iter().chain(&id.to_be_bytes()).copied().collect();
+
+        let cipher = XChaCha20Poly1305::new(&subkey(&key, CIPHER_KEY_PERSONAL)?);
+        let nonce_key = subkey(&key, NONCE_KEY_PERSONAL)?;
+        let nonce = nonce(&nonce_key, &header, plaintext.as_slice())?;
+        let payload = Payload {
+            msg: plaintext.as_slice(),
+            aad: &header,
+        };
+        let ciphertext = cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| Error::Crypto(String::from("encryption failed")))?;
+
+        let mut b = header;
+        b.extend_from_slice(&nonce);
+        b.extend(ciphertext);
+        Value::bytes(b)
+    }
+
+    fn from_value_in(v: &Value, ctx: &ContentsContext) -> Result<Self, Error> {
+        let b = v.get_bytes()?;
+        let header_len = TAG.len() + 4;
+        if !b.starts_with(TAG) || b.len() < header_len + NONCE_LEN {
+            return Err(Error::Crypto(String::from("value is not encrypted")));
+        }
+        let (header, rest) = b.split_at(header_len);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+        let mut id = [0; 4];
+        id.copy_from_slice(&header[TAG.len()..]);
+        let id = u32::from_be_bytes(id);
+        let key = match ctx.keys::<K>()?.key(id)? {
+            Some(key) => key,
+            None => return Err(Error::Crypto(format!("unknown key id: {id}"))),
+        };
+
+        let cipher = XChaCha20Poly1305::new(&subkey(&key, CIPHER_KEY_PERSONAL)?);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: header,
+        };
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(nonce), payload)
+            .map_err(|_| Error::Crypto(String::from("decryption failed")))?;
+
+        let v = Value::of_bin(T::content_type().ty()?, plaintext)?;
+        T::from_value_in(&v, ctx).map(Encrypted::new)
+    }
+}
@@ -0,0 +1,336 @@
+use crate::internal::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Char(char),
+    AnyChar,
+    Star,
+    Class(bool, Vec<(char, char)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    // `**`, matches any number of steps
+    Recursive,
+    Pattern(Vec<Token>),
+}
+
+/// Glob pattern matched against the steps of a path. Patterns are separated by `/`, within a
+/// step `*` matches any sequence of characters, `?` matches a single character and `[a-z]`
+/// or `[!a-z]` match character classes. A `**` step matches any number of steps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+    pattern: String,
+    segments: Vec<Segment>,
+}
+
+impl Glob {
+    /// Parse a glob pattern
+    pub fn new(pattern: impl AsRef<str>) -> Result<Glob, Error> {
+        let pattern = pattern.as_ref();
+        let mut segments = Vec::new();
+        for s in pattern.split('/').filter(|x| !x.is_empty()) {
+            if s == "**" {
+                segments.push(Segment::Recursive);
+            } else {
+                segments.push(Segment::Pattern(Glob::parse_segment(
+                    pattern,
+                    &Path::unescape_segment(s),
+                )?));
+            }
+        }
+        Ok(Glob {
+            pattern: pattern.to_string(),
+            segments,
+        })
+    }
+
+    fn parse_segment(pattern: &str, s: &str) -> Result<Vec<Token>, Error> {
+        let mut tokens = Vec::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '*' => Token::Star,
+                '?' => Token::AnyChar,
+                '[' => {
+                    let mut negated = false;
+                    let mut ranges = Vec::new();
+                    let mut prev = None;
+                    loop {
+                        match chars.next() {
+                            Some(']') if prev.is_some() || !ranges.is_empty() => break,
+                            Some('!') if prev.is_none() && ranges.is_empty() && !negated => {
+                                negated = true
+                            }
+                            Some('-') if prev.is_some() => {
+                                let end = chars.next().filter(|x| *x != ']').ok_or_else(|| {
+                                    Error::Parse(format!("invalid character range: {pattern}"))
+                                })?;
+                                ranges.push((prev.take().unwrap(), end));
+                            }
+                            Some(x) => {
+                                if let Some(p) = prev.replace(x) {
+                                    ranges.push((p, p));
+                                }
+                            }
+                            None => {
+                                return Err(Error::Parse(format!(
+                                    "unterminated character class: {pattern}"
+                                )))
+                            }
+                        }
+                    }
+                    if let Some(p) = prev {
+                        ranges.push((p, p));
+                    }
+                    Token::Class(negated, ranges)
+                }
+                c => Token::Char(c),
+            };
+            tokens.push(token);
+        }
+        Ok(tokens)
+    }
+
+    /// Get the original pattern
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns true if the path matches the pattern
+    pub fn matches(&self, path: &PathBuf) -> bool {
+        Glob::match_steps(&self.segments, path.steps())
+    }
+
+    /// Returns true if a path starting with `prefix` could match the pattern, this is used to
+    /// skip subtrees that can't contain any matches
+    pub fn matches_prefix(&self, prefix: &PathBuf) -> bool {
+        Glob::match_prefix(&self.segments, prefix.steps())
+    }
+
+    fn match_steps(segments: &[Segment], steps: &[String]) -> bool {
+        Glob::match_table(
+            segments,
+            steps,
+            |segment| *segment == Segment::Recursive,
+            |segment, step| match segment {
+                Segment::Pattern(tokens) => {
+                    let step: Vec<_> = step.chars().collect();
+                    Glob::match_tokens(tokens, &step)
+                }
+                Segment::Recursive => unreachable!(),
+            },
+        )
+    }
+
+    fn match_prefix(segments: &[Segment], steps: &[String]) -> bool {
+        match (segments.split_first(), steps.split_first()) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some((Segment::Recursive, _)), Some(_)) => true,
+            (Some((Segment::Pattern(tokens), rest)), Some((step, steps))) => {
+                let step: Vec<_> = step.chars().collect();
+                Glob::match_tokens(tokens, &step) && Glob::match_prefix(rest, steps)
+            }
+        }
+    }
+
+    fn match_tokens(tokens: &[Token], s: &[char]) -> bool {
+        Glob::match_table(
+            tokens,
+            s,
+            |token| *token == Token::Star,
+            |token, c| match token {
+                Token::Char(x) => x == c,
+                Token::AnyChar => true,
+                Token::Class(negated, ranges) => {
+                    ranges.iter().any(|(a, b)| a <= c && c <= b) != *negated
+                }
+                Token::Star => unreachable!(),
+            },
+        )
+    }
+
+    // Matches `items` against `patterns` where wildcard patterns match any number of items,
+    // filling in a table over (pattern, item) one row at a time so matching takes
+    // O(patterns * items) time instead of backtracking
+    fn match_table<P, I>(
+        patterns: &[P],
+        items: &[I],
+        is_wildcard: impl Fn(&P) -> bool,
+        matches: impl Fn(&P, &I) -> bool,
+    ) -> bool {
+        let n = items.len();
+
+        // `next[j]` is true when the remaining patterns match `items[j..]`
+        let mut next = vec![false; n + 1];
+        next[n] = true;
+        for p in patterns.iter().rev() {
+            let mut row = vec![false; n + 1];
+            for j in (0..=n).rev() {
+                row[j] = if is_wildcard(p) {
+                    next[j] || (j < n && row[j + 1])
+                } else {
+                    j < n && next[j + 1] && matches(p, &items[j])
+                };
+            }
+            next = row;
+        }
+        next[0]
+    }
+}
+
+impl std::fmt::Display for Glob {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fmt.write_str(&self.pattern)
+    }
+}
+
+impl std::str::FromStr for Glob {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Glob, Error> {
+        Glob::new(s)
+    }
+}
+
+/// Iterator over the paths of contents matching a glob pattern, values aren't read. See
+/// `Tree::glob_paths` and `Store::glob_paths`
+pub struct GlobPaths<'a, T: Contents> {
+    tree: Option<Tree<'a, T>>,
+    glob: Glob,
+    stack: Vec<PathBuf>,
+}
+
+impl<'a, T: Contents> GlobPaths<'a, T> {
+    fn new(tree: Option<Tree<'a, T>>, glob: Glob) -> GlobPaths<'a, T> {
+        GlobPaths {
+            tree,
+            glob,
+            stack: vec![PathBuf::new()],
+        }
+    }
+
+    fn visit(&mut self, path: PathBuf) -> Result<Option<PathBuf>, Error> {
+        let tree = match &self.tree {
+            Some(tree) => tree,
+            None => return Ok(None),
+        };
+
+        if !path.is_empty() && tree.mem(&path)? {
+            if !self.glob.matches(&path) {
+                return Ok(None);
+            }
+            return Ok(Some(path));
+        }
+
+        let mut children = Vec::new();
+        for p in tree.list(&path)? {
+            if let Some(step) = p.segments()?.pop() {
+                let child = path.join(step)?;
+                if self.glob.matches_prefix(&child) {
+                    children.push(child);
+                }
+            }
+        }
+
+        // Children are popped from the end of the stack, so they're visited in sorted order
+        children.sort_by(|a, b| b.cmp(a));
+        self.stack.extend(children);
+        Ok(None)
+    }
+}
+
+impl<'a, T: Contents> Iterator for GlobPaths<'a, T> {
+    type Item = Result<PathBuf, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(path) = self.stack.pop() {
+            match self.visit(path) {
+                Ok(Some(x)) => return Some(Ok(x)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the contents matching a glob pattern, see `Tree::glob` and `Store::glob`
+pub struct GlobIter<'a, T: Contents> {
+    paths: GlobPaths<'a, T>,
+}
+
+impl<'a, T: Contents> GlobIter<'a, T> {
+    fn new(tree: Option<Tree<'a, T>>, glob: Glob) -> GlobIter<'a, T> {
+        GlobIter {
+            paths: GlobPaths::new(tree, glob),
+        }
+    }
+
+    fn read(&self, path: PathBuf) -> Result<Option<(PathBuf, T)>, Error> {
+        match &self.paths.tree {
+            Some(tree) => tree.find(&path).map(|x| x.map(|v| (path, v))),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'a, T: Contents> Iterator for GlobIter<'a, T> {
+    type Item = Result<(PathBuf, T), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(path) = self.paths.next() {
+            match path.and_then(|path| self.read(path)) {
+                Ok(Some(x)) => return Some(Ok(x)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T: Contents> Tree<'a, T> {
+    /// Find contents with paths matching a glob pattern, see `Glob`. The tree is traversed
+    /// lazily and only subtrees that can contain matches are visited
+    pub fn glob(&self, pattern: impl AsRef<str>) -> Result<GlobIter<'a, T>, Error> {
+        Ok(GlobIter::new(Some(self.try_clone()?), Glob::new(pattern)?))
+    }
+
+    /// Find the paths of contents matching a glob pattern without reading the values, see
+    /// `Tree::glob`
+    pub fn glob_paths(&self, pattern: impl AsRef<str>) -> Result<GlobPaths<'a, T>, Error> {
+        Ok(GlobPaths::new(Some(self.try_clone()?), Glob::new(pattern)?))
+    }
+}
+
+impl<'a, T: Contents> Store<'a, T> {
+    /// Find contents with paths matching a glob pattern, see `Tree::glob`
+    pub fn glob(&self, pattern: impl AsRef<str>) -> Result<GlobIter<'a, T>, Error> {
+        let tree = self.find_tree(PathBuf::new())?;
+        Ok(GlobIter::new(tree, Glob::new(pattern)?))
+    }
+
+    /// Find the paths of contents matching a glob pattern without reading the values, see
+    /// `Tree::glob_paths`
+    pub fn glob_paths(&self, pattern: impl AsRef<str>) -> Result<GlobPaths<'a, T>, Error> {
+        let tree = self.find_tree(PathBuf::new())?;
+        Ok(GlobPaths::new(tree, Glob::new(pattern)?))
+    }
+}
+
+impl<'a, T: Contents> ReadOnlyStore<'a, T> {
+    /// Find contents with paths matching a glob pattern, see `Tree::glob`
+    pub fn glob(&self, pattern: impl AsRef<str>) -> Result<GlobIter<'a, T>, Error> {
+        let tree = self.find_tree(PathBuf::new())?;
+        Ok(GlobIter::new(tree, Glob::new(pattern)?))
+    }
+
+    /// Find the paths of contents matching a glob pattern without reading the values, see
+    /// `Tree::glob_paths`
+    pub fn glob_paths(&self, pattern: impl AsRef<str>) -> Result<GlobPaths<'a, T>, Error> {
+        let tree = self.find_tree(PathBuf::new())?;
+        Ok(GlobPaths::new(tree, Glob::new(pattern)?))
+    }
+}
@@ -15,23 +15,34 @@ impl<'a> PartialEq for Hash<'a> {
 impl<'a> Hash<'a> {
     /// Convert from string to Hash
     pub fn of_string<T: Contents>(
-        repo: &'a Repo<T>,
+        repo: impl Into<RepoRef<'a, T>>,
         s: impl AsRef<str>,
     ) -> Result<Hash<'a>, Error> {
+        let repo = repo.into();
         let s = s.as_ref();
         let ptr = unsafe { irmin_hash_of_string(repo.ptr, s.as_ptr() as *mut _, s.len() as i64) };
         check!(repo.ptr, ptr);
         Ok(Hash {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
         })
     }
 
     /// Convert from Hash to String
-    pub fn to_string<T: Contents>(&self) -> Result<String, Error> {
+    pub fn to_string(&self) -> Result<String, Error> {
         let s = unsafe { irmin_hash_to_string(self.repo.ptr, self.ptr) };
         IrminString::wrap(s).map(|x| x.into())
     }
+
+    /// Copy the hash into a `HashBuf` that doesn't depend on the repo
+    pub fn to_buf(&self) -> Result<HashBuf, Error> {
+        let t = unsafe { irmin_type_hash(self.repo.ptr) };
+        check!(self.repo.ptr, t);
+        let s = unsafe { irmin_value_to_bin(t, self.ptr as *mut _) };
+        unsafe { irmin_type_free(t) }
+        let s = IrminString::wrap(s)?;
+        HashBuf::new(self.repo.hash_type, s.as_slice())
+    }
 }
 
 impl<'a> Drop for Hash<'a> {
@@ -39,3 +50,108 @@ impl<'a> Drop for Hash<'a> {
         unsafe { irmin_hash_free(self.ptr) }
     }
 }
+
+/// Owned hash value that is independent of a repo, displayed and parsed as `<hash type>:<hex>`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, std::hash::Hash)]
+pub struct HashBuf {
+    hash_type: HashType,
+    bytes: Vec<u8>,
+}
+
+impl HashBuf {
+    /// Create a hash from raw bytes, the length must match the hash type
+    pub fn new(hash_type: HashType, bytes: impl Into<Vec<u8>>) -> Result<HashBuf, Error> {
+        let bytes = bytes.into();
+        if bytes.len() != hash_type.size() {
+            return Err(Error::Parse(format!(
+                "invalid {hash_type} hash length: {}",
+                bytes.len()
+            )));
+        }
+        Ok(HashBuf { hash_type, bytes })
+    }
+
+    /// Parse a hex string
+    pub fn of_hex(hash_type: HashType, s: impl AsRef<str>) -> Result<HashBuf, Error> {
+        HashBuf::new(hash_type, of_hex(s.as_ref())?)
+    }
+
+    /// Hash function used to compute the hash
+    pub fn hash_type(&self) -> HashType {
+        self.hash_type
+    }
+
+    /// Raw hash bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Hex encoding of the hash bytes, without the hash type
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.bytes)
+    }
+
+    /// Convert to a `Hash` for the given repo, the repo must use the same hash type
+    pub fn to_hash<'a, T: Contents>(
+        &self,
+        repo: impl Into<RepoRef<'a, T>>,
+    ) -> Result<Hash<'a>, Error> {
+        let repo = repo.into();
+        if repo.config.hash_type != self.hash_type {
+            return Err(Error::Type(format!(
+                "hash type mismatch: expected {}, got {}",
+                repo.config.hash_type, self.hash_type
+            )));
+        }
+        let t = unsafe { irmin_type_hash(repo.ptr) };
+        check!(repo.ptr, t);
+        let ptr = unsafe {
+            irmin_value_of_bin(t, self.bytes.as_ptr() as *mut _, self.bytes.len() as i64)
+        };
+        unsafe { irmin_type_free(t) }
+        check!(repo.ptr, ptr);
+        Ok(Hash {
+            ptr: ptr as *mut _,
+            repo: UntypedRepo::new(&repo),
+        })
+    }
+}
+
+impl std::fmt::Display for HashBuf {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}:{}", self.hash_type, self.to_hex())
+    }
+}
+
+impl std::str::FromStr for HashBuf {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<HashBuf, Error> {
+        let (hash_type, hex) = s
+            .split_once(':')
+            .ok_or_else(|| Error::Parse(format!("invalid hash: {s}")))?;
+        HashBuf::of_hex(hash_type.parse()?, hex)
+    }
+}
+
+impl<'a> TryFrom<&Hash<'a>> for HashBuf {
+    type Error = Error;
+
+    fn try_from(h: &Hash<'a>) -> Result<HashBuf, Error> {
+        h.to_buf()
+    }
+}
+
+// Serialized as a `(hash type, hex)` pair so the hash type is preserved
+impl serde::Serialize for HashBuf {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&(self.hash_type, self.to_hex()), s)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for HashBuf {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<HashBuf, D::Error> {
+        let (hash_type, s): (HashType, String) = serde::Deserialize::deserialize(d)?;
+        HashBuf::of_hex(hash_type, s).map_err(serde::de::Error::custom)
+    }
+}
@@ -9,10 +9,18 @@ pub struct Info<'a> {
 impl<'a> Info<'a> {
     /// Create new commit info
     pub fn new<T: Contents>(
-        repo: &Repo<T>,
+        repo: impl Into<RepoRef<'a, T>>,
         author: impl AsRef<str>,
         message: impl AsRef<str>,
-    ) -> Result<Info, Error> {
+    ) -> Result<Info<'a>, Error> {
+        Info::of_untyped(&UntypedRepo::new(repo), author, message)
+    }
+
+    pub(crate) fn of_untyped(
+        repo: &UntypedRepo<'a>,
+        author: impl AsRef<str>,
+        message: impl AsRef<str>,
+    ) -> Result<Info<'a>, Error> {
         let message = cstring(message);
         let author = cstring(author);
         let ptr = unsafe {
@@ -25,7 +33,7 @@ impl<'a> Info<'a> {
         check!(repo.ptr, ptr);
         Ok(Info {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: repo.clone(),
         })
     }
 
@@ -5,7 +5,7 @@ pub struct IrminString(pub *mut crate::bindings::IrminString, pub usize);
 
 impl std::fmt::Debug for IrminString {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
-        self.as_str().fmt(fmt)
+        String::from_utf8_lossy(self.as_slice()).fmt(fmt)
     }
 }
 
@@ -34,9 +34,29 @@ impl IrminString {
         Ok(IrminString(s, len))
     }
 
-    /// Access IrminString as str
+    /// Access IrminString as str, panics if the string is not valid UTF-8. Use
+    /// `IrminString::to_str` when the string may contain binary data
     pub fn as_str(&self) -> &str {
-        self.as_ref()
+        self.to_str().expect("IrminString contains invalid UTF-8")
+    }
+
+    /// Access IrminString as str, returns an error if the string is not valid UTF-8
+    pub fn to_str(&self) -> Result<&str, Error> {
+        std::str::from_utf8(self.as_slice()).map_err(Error::from)
+    }
+
+    /// Access IrminString as a C string, returns an error if the string contains a NUL byte
+    pub fn to_cstr(&self) -> Result<&std::ffi::CStr, Error> {
+        let b = unsafe {
+            let data = irmin_string_data(self.0);
+            std::slice::from_raw_parts(data as *const u8, self.1 + 1)
+        };
+        std::ffi::CStr::from_bytes_with_nul(b).map_err(|e| Error::Parse(e.to_string()))
+    }
+
+    /// Convert to `String`, replacing invalid UTF-8 sequences with `U+FFFD`
+    pub fn to_string_lossy(&self) -> std::borrow::Cow<'_, str> {
+        String::from_utf8_lossy(self.as_slice())
     }
 
     /// Access bytes of IrminString
@@ -60,29 +80,9 @@ impl AsRef<[u8]> for IrminString {
     }
 }
 
-impl AsRef<str> for IrminString {
-    fn as_ref(&self) -> &str {
-        unsafe {
-            let data = irmin_string_data(self.0);
-            let s = std::slice::from_raw_parts_mut(data as *mut u8, self.1);
-            std::str::from_utf8_unchecked(s)
-        }
-    }
-}
-
-impl AsRef<std::ffi::CStr> for IrminString {
-    fn as_ref(&self) -> &std::ffi::CStr {
-        unsafe {
-            let data = irmin_string_data(self.0);
-            let b = std::slice::from_raw_parts_mut(data as *mut u8, self.1 + 1);
-            std::ffi::CStr::from_bytes_with_nul_unchecked(b)
-        }
-    }
-}
-
 impl From<IrminString> for String {
     fn from(x: IrminString) -> String {
-        x.as_str().to_string()
+        x.to_string_lossy().into_owned()
     }
 }
 
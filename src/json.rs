@@ -0,0 +1,281 @@
+use crate::internal::*;
+
+/// Contents that are stored as JSON, implemented for `Json` and `JsonValue`
+pub trait JsonContents: Contents {
+    fn to_json_value(&self) -> JsonValue;
+    fn from_json_value(v: JsonValue) -> Result<Self, Error>;
+}
+
+impl JsonContents for JsonValue {
+    fn to_json_value(&self) -> JsonValue {
+        self.clone()
+    }
+
+    fn from_json_value(v: JsonValue) -> Result<Self, Error> {
+        Ok(v)
+    }
+}
+
+impl JsonContents for Json {
+    fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(self.clone())
+    }
+
+    fn from_json_value(v: JsonValue) -> Result<Self, Error> {
+        serde_json::from_value(v).map_err(Error::from)
+    }
+}
+
+// Set the value at `pointer`, the parent must already exist. The last token may name a new
+// object field or be `-` to append to an array
+fn set_pointer(doc: &mut JsonValue, pointer: &str, value: JsonValue) -> Result<(), Error> {
+    if let Some(x) = doc.pointer_mut(pointer) {
+        *x = value;
+        return Ok(());
+    }
+
+    let (parent, last) = match pointer.rsplit_once('/') {
+        Some(x) => x,
+        None => return Err(Error::Parse(format!("invalid JSON pointer: {pointer}"))),
+    };
+    let last = last.replace("~1", "/").replace("~0", "~");
+    match doc.pointer_mut(parent) {
+        Some(JsonValue::Object(obj)) => {
+            obj.insert(last, value);
+            Ok(())
+        }
+        Some(JsonValue::Array(arr)) if last == "-" => {
+            arr.push(value);
+            Ok(())
+        }
+        _ => Err(Error::Parse(format!("JSON pointer not found: {pointer}"))),
+    }
+}
+
+impl<'a, T: JsonContents> Store<'a, T> {
+    /// Find the value at the given path and return the part of it referenced by a JSON
+    /// pointer, for example `/a/b/0`
+    pub fn find_pointer(
+        &self,
+        path: impl IntoPath,
+        pointer: impl AsRef<str>,
+    ) -> Result<Option<JsonValue>, Error> {
+        let v = match self.find(path)? {
+            Some(v) => v.to_json_value(),
+            None => return Ok(None),
+        };
+        Ok(v.pointer(pointer.as_ref()).cloned())
+    }
+
+    /// Evaluate a JSONPath query against the values with paths matching a glob pattern,
+    /// returning each node matched by the query along with the path of the value it was found
+    /// in. Requires the `jsonpath` feature
+    #[cfg(feature = "jsonpath")]
+    pub fn query(
+        &self,
+        pattern: impl AsRef<str>,
+        jsonpath: impl AsRef<str>,
+    ) -> Result<Vec<(PathBuf, JsonValue)>, Error> {
+        let query = serde_json_path::JsonPath::parse(jsonpath.as_ref())
+            .map_err(|e| Error::Parse(e.to_string()))?;
+        let mut dest = Vec::new();
+        for x in self.glob(pattern)? {
+            let (path, v) = x?;
+            let v = v.to_json_value();
+            for node in query.query(&v).all() {
+                dest.push((path.clone(), node.clone()))
+            }
+        }
+        Ok(dest)
+    }
+
+    // Read the value at `path`, update it using `f` and write it back using `test_and_set`. A
+    // missing value is passed to `f` as `null`
+    fn update_json(
+        &mut self,
+        path: impl IntoPath,
+        info: Info,
+        f: impl FnOnce(&mut JsonValue) -> Result<(), Error>,
+    ) -> Result<bool, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
+        let old = self.find(&*path)?;
+        let mut doc = match &old {
+            Some(v) => v.to_json_value(),
+            None => JsonValue::Null,
+        };
+        f(&mut doc)?;
+        let new = T::from_json_value(doc)?;
+        self.test_and_set(&*path, old.as_ref(), Some(&new), info)
+    }
+
+    /// Update the part of a value referenced by a JSON pointer using `test_and_set`, returns
+    /// `false` if the value was modified concurrently
+    pub fn update_pointer(
+        &mut self,
+        path: impl IntoPath,
+        pointer: impl AsRef<str>,
+        value: JsonValue,
+        info: Info,
+    ) -> Result<bool, Error> {
+        self.update_json(path, info, |doc| set_pointer(doc, pointer.as_ref(), value))
+    }
+
+    /// Apply a JSON Patch (RFC 6902) to the value at the given path using `test_and_set`,
+    /// returns `false` if the value was modified concurrently. The value is unchanged if any
+    /// operation fails. Requires the `json-patch` feature
+    #[cfg(feature = "json-patch")]
+    pub fn apply_patch(
+        &mut self,
+        path: impl IntoPath,
+        patch: &JsonPatch,
+        info: Info,
+    ) -> Result<bool, Error> {
+        self.update_json(path, info, |doc| {
+            json_patch::patch(doc, patch).map_err(|e| Error::Patch(e.to_string()))
+        })
+    }
+
+    /// Apply a JSON Merge Patch (RFC 7396) to the value at the given path using `test_and_set`,
+    /// returns `false` if the value was modified concurrently. Requires the `json-patch` feature
+    #[cfg(feature = "json-patch")]
+    pub fn apply_merge_patch(
+        &mut self,
+        path: impl IntoPath,
+        patch: &JsonValue,
+        info: Info,
+    ) -> Result<bool, Error> {
+        self.update_json(path, info, |doc| {
+            json_patch::merge(doc, patch);
+            Ok(())
+        })
+    }
+}
+
+/// Compute a JSON Patch that transforms `old` into `new`. Requires the `json-patch` feature
+#[cfg(feature = "json-patch")]
+pub fn diff_json<T: JsonContents>(old: &T, new: &T) -> JsonPatch {
+    json_patch::diff(&old.to_json_value(), &new.to_json_value())
+}
+
+// Add the fields of `obj` to `tree` under `path`. Non-empty objects are mapped to nodes and all
+// other values, including empty objects, are converted to contents using `leaf`, since Irmin
+// trees can't contain empty nodes. Keys are escaped using `Path::escape_segment`
+fn add_json<T: Contents>(
+    tree: &mut Tree<T>,
+    path: &PathBuf,
+    obj: &Json,
+    leaf: &impl Fn(&JsonValue) -> Result<T, Error>,
+) -> Result<(), Error> {
+    for (k, v) in obj {
+        let path = path.join(Path::escape_segment(k))?;
+        match v {
+            JsonValue::Object(obj) if !obj.is_empty() => add_json(tree, &path, obj, leaf)?,
+            _ => tree.add(&path, &leaf(v)?, None)?,
+        }
+    }
+    Ok(())
+}
+
+// Read the JSON value stored under `path` by `add_json`, contents are converted using `leaf`
+fn read_json<T: Contents>(
+    tree: &Tree<T>,
+    path: &PathBuf,
+    leaf: &impl Fn(T) -> Result<JsonValue, Error>,
+) -> Result<JsonValue, Error> {
+    if let Some(v) = tree.find(path)? {
+        return leaf(v);
+    }
+
+    let mut obj = Json::new();
+    for p in tree.list(path)? {
+        if let Some(step) = p.segments()?.pop() {
+            let v = read_json(tree, &path.join(&step)?, leaf)?;
+            obj.insert(Path::unescape_segment(step), v);
+        }
+    }
+    Ok(JsonValue::Object(obj))
+}
+
+/// Stores JSON documents as trees instead of a single value: objects are mapped to nodes and
+/// all other values, including arrays and empty objects, are stored as leaves. This allows
+/// merges and diffs to work on individual fields. Object keys are escaped using
+/// `Path::escape_segment`
+pub struct JsonTree;
+
+impl JsonTree {
+    /// Convert a JSON value to a tree
+    pub fn to_tree<'a>(
+        repo: impl Into<RepoRef<'a, JsonValue>>,
+        value: &JsonValue,
+    ) -> Result<Tree<'a, JsonValue>, Error> {
+        let repo = repo.into();
+        match value {
+            JsonValue::Object(obj) if !obj.is_empty() => {
+                let mut tree = Tree::new(&repo)?;
+                add_json(&mut tree, &PathBuf::new(), obj, &|v| Ok(v.clone()))?;
+                Ok(tree)
+            }
+            _ => Tree::from_contents(&repo, value, None),
+        }
+    }
+
+    /// Convert a tree created using `JsonTree::to_tree` back to a JSON value
+    pub fn from_tree(tree: &Tree<JsonValue>) -> Result<JsonValue, Error> {
+        read_json(tree, &PathBuf::new(), &Ok)
+    }
+    /// Store a JSON value as a tree at the given path, creating a new commit
+    pub fn set(
+        store: &mut Store<JsonValue>,
+        path: impl IntoPath,
+        value: &JsonValue,
+        info: Info,
+    ) -> Result<bool, Error> {
+        let tree = JsonTree::to_tree(&store.repo, value)?;
+        store.set_tree(path, &tree, info)
+    }
+
+    /// Read a JSON value stored using `JsonTree::set`
+    pub fn find(store: &Store<JsonValue>, path: impl IntoPath) -> Result<Option<JsonValue>, Error> {
+        match store.find_tree(path)? {
+            Some(tree) => JsonTree::from_tree(&tree).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'a, T: Contents> Tree<'a, T> {
+    /// Convert a tree to nested JSON objects, contents are encoded using `Value::to_json` and
+    /// object keys are decoded from steps using `Path::unescape_segment`, the same as
+    /// `JsonTree`
+    pub fn to_json(&self) -> Result<JsonValue, Error> {
+        let ctx = &self.repo.context;
+        read_json(self, &PathBuf::new(), &|v: T| {
+            let s = v.to_value_in(ctx)?.to_json()?;
+            serde_json::from_slice(s.as_slice()).map_err(Error::from)
+        })
+    }
+
+    /// Create a tree from nested JSON objects, the inverse of `Tree::to_json`. Keys are escaped
+    /// using `Path::escape_segment` and non-empty objects are converted to nodes. All other
+    /// values are decoded as contents using `Value::of_json`, including nested empty objects,
+    /// which is an error for contents that can't represent them
+    pub fn from_json(
+        repo: impl Into<RepoRef<'a, T>>,
+        value: &JsonValue,
+    ) -> Result<Tree<'a, T>, Error> {
+        let repo = repo.into();
+        let ctx = &repo.config.context;
+        let leaf = |v: &JsonValue| {
+            let v = Value::of_json(T::ty()?, serde_json::to_string(v)?)?;
+            T::from_value_in(&v, ctx)
+        };
+        match value {
+            JsonValue::Object(obj) => {
+                let mut tree = Tree::new(&repo)?;
+                add_json(&mut tree, &PathBuf::new(), obj, &leaf)?;
+                Ok(tree)
+            }
+            _ => Tree::from_contents(&repo, &leaf(value)?, None),
+        }
+    }
+}
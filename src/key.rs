@@ -36,9 +36,187 @@ impl<'a> Drop for KindedKey<'a> {
 impl<'a> KindedKey<'a> {
     /// Convert KindedKey to string representation using `Irmin.Type.to_string`
     pub fn to_string(&self) -> Result<IrminString, Error> {
-        let t = unsafe { irmin_type_commit_key(self.repo.ptr) };
+        let t = unsafe { irmin_type_kinded_key(self.repo.ptr) };
         let s = unsafe { irmin_value_to_string(t, self.ptr as *mut _) };
         unsafe { irmin_type_free(t) }
         IrminString::wrap(s)
     }
+
+    /// Returns true if the key refers to contents
+    pub fn is_contents(&self) -> bool {
+        unsafe { irmin_kinded_key_is_contents(self.repo.ptr, self.ptr) }
+    }
+
+    /// Returns true if the key refers to a node
+    pub fn is_node(&self) -> bool {
+        unsafe { irmin_kinded_key_is_node(self.repo.ptr, self.ptr) }
+    }
+
+    /// Determine the kind of object the key refers to
+    pub fn into_kind(self) -> KeyKind<'a> {
+        if self.is_contents() {
+            KeyKind::Contents(ContentsKey(self))
+        } else {
+            KeyKind::Node(NodeKey(self))
+        }
+    }
+}
+
+/// A `KindedKey` split by the kind of object it refers to, see `KindedKey::into_kind`
+pub enum KeyKind<'a> {
+    Contents(ContentsKey<'a>),
+    Node(NodeKey<'a>),
+}
+
+/// Key that refers to contents, use `Contents::of_key` to read the value
+pub struct ContentsKey<'a>(KindedKey<'a>);
+
+/// Key that refers to a node, use `Tree::of_key` to read the tree
+pub struct NodeKey<'a>(KindedKey<'a>);
+
+impl<'a> std::ops::Deref for ContentsKey<'a> {
+    type Target = KindedKey<'a>;
+
+    fn deref(&self) -> &KindedKey<'a> {
+        &self.0
+    }
+}
+
+impl<'a> std::ops::Deref for NodeKey<'a> {
+    type Target = KindedKey<'a>;
+
+    fn deref(&self) -> &KindedKey<'a> {
+        &self.0
+    }
+}
+
+impl<'a> From<ContentsKey<'a>> for KindedKey<'a> {
+    fn from(k: ContentsKey<'a>) -> KindedKey<'a> {
+        k.0
+    }
+}
+
+impl<'a> From<NodeKey<'a>> for KindedKey<'a> {
+    fn from(k: NodeKey<'a>) -> KindedKey<'a> {
+        k.0
+    }
+}
+
+/// Owned commit key that is independent of a repo, stored using Irmin's binary encoding and
+/// displayed and parsed as `<hash type>:<hex>`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, std::hash::Hash)]
+pub struct CommitKeyBuf {
+    hash_type: HashType,
+    bytes: Vec<u8>,
+}
+
+impl<'a> CommitKey<'a> {
+    /// Copy the key into a `CommitKeyBuf` that doesn't depend on the repo
+    pub fn to_buf(&self) -> Result<CommitKeyBuf, Error> {
+        let t = unsafe { irmin_type_commit_key(self.repo.ptr) };
+        check!(self.repo.ptr, t);
+        let s = unsafe { irmin_value_to_bin(t, self.ptr as *mut _) };
+        unsafe { irmin_type_free(t) }
+        let s = IrminString::wrap(s)?;
+        CommitKeyBuf::new(self.repo.hash_type, s.as_slice())
+    }
+}
+
+impl CommitKeyBuf {
+    /// Create a commit key from its binary encoding, which contains at least a hash of the given
+    /// type. Git and in-memory stores use the hash itself, pack stores add its location
+    pub fn new(hash_type: HashType, bytes: impl Into<Vec<u8>>) -> Result<CommitKeyBuf, Error> {
+        let bytes = bytes.into();
+        if bytes.len() < hash_type.size() {
+            return Err(Error::Parse(format!(
+                "invalid {hash_type} commit key length: {}",
+                bytes.len()
+            )));
+        }
+        Ok(CommitKeyBuf { hash_type, bytes })
+    }
+
+    /// Parse a hex string
+    pub fn of_hex(hash_type: HashType, s: impl AsRef<str>) -> Result<CommitKeyBuf, Error> {
+        CommitKeyBuf::new(hash_type, of_hex(s.as_ref())?)
+    }
+
+    /// Hash function used by the store
+    pub fn hash_type(&self) -> HashType {
+        self.hash_type
+    }
+
+    /// Binary encoding of the key
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Hex encoding of the key, without the hash type
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.bytes)
+    }
+
+    /// Convert to a `CommitKey` for the given repo, the repo must use the same hash type
+    pub fn to_key<'a, T: Contents>(
+        &self,
+        repo: impl Into<RepoRef<'a, T>>,
+    ) -> Result<CommitKey<'a>, Error> {
+        let repo = repo.into();
+        if repo.config.hash_type != self.hash_type {
+            return Err(Error::Type(format!(
+                "hash type mismatch: expected {}, got {}",
+                repo.config.hash_type, self.hash_type
+            )));
+        }
+        let t = unsafe { irmin_type_commit_key(repo.ptr) };
+        check!(repo.ptr, t);
+        let ptr = unsafe {
+            irmin_value_of_bin(t, self.bytes.as_ptr() as *mut _, self.bytes.len() as i64)
+        };
+        unsafe { irmin_type_free(t) }
+        check!(repo.ptr, ptr);
+        Ok(CommitKey {
+            ptr: ptr as *mut _,
+            repo: UntypedRepo::new(&repo),
+        })
+    }
+}
+
+impl std::fmt::Display for CommitKeyBuf {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{}:{}", self.hash_type, self.to_hex())
+    }
+}
+
+impl std::str::FromStr for CommitKeyBuf {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<CommitKeyBuf, Error> {
+        let (hash_type, hex) = s
+            .split_once(':')
+            .ok_or_else(|| Error::Parse(format!("invalid commit key: {s}")))?;
+        CommitKeyBuf::of_hex(hash_type.parse()?, hex)
+    }
+}
+
+impl<'a> TryFrom<&CommitKey<'a>> for CommitKeyBuf {
+    type Error = Error;
+
+    fn try_from(k: &CommitKey<'a>) -> Result<CommitKeyBuf, Error> {
+        k.to_buf()
+    }
+}
+
+// Serialized as a `(hash type, hex)` pair so the hash type is preserved
+impl serde::Serialize for CommitKeyBuf {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(&(self.hash_type, self.to_hex()), s)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CommitKeyBuf {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<CommitKeyBuf, D::Error> {
+        let (hash_type, s): (HashType, String) = serde::Deserialize::deserialize(d)?;
+        CommitKeyBuf::of_hex(hash_type, s).map_err(serde::de::Error::custom)
+    }
 }
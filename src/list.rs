@@ -0,0 +1,148 @@
+use crate::internal::*;
+
+/// Kind of object stored at a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Contents,
+    Node,
+}
+
+/// Directory entry returned by `list_entries`
+pub struct Entry<'a> {
+    pub step: String,
+    pub kind: Kind,
+    pub hash: Option<Hash<'a>>,
+}
+
+/// Pagination options for `list_entries_with`, entries are sorted by step before the options
+/// are applied
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ListOptions {
+    pub start_after: Option<String>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+impl ListOptions {
+    /// Create empty options, returning all entries
+    pub fn new() -> ListOptions {
+        ListOptions::default()
+    }
+
+    /// Skip entries up to and including `step`
+    pub fn start_after(mut self, step: impl Into<String>) -> ListOptions {
+        self.start_after = Some(step.into());
+        self
+    }
+
+    /// Skip the first `n` entries, applied after `start_after`
+    pub fn offset(mut self, n: usize) -> ListOptions {
+        self.offset = n;
+        self
+    }
+
+    /// Return at most `n` entries
+    pub fn limit(mut self, n: usize) -> ListOptions {
+        self.limit = Some(n);
+        self
+    }
+}
+
+impl<'a, T: Contents> Tree<'a, T> {
+    /// List the children of a path along with their kind and hash, sorted by step
+    pub fn list_entries(&self, path: impl IntoPath) -> Result<Vec<Entry<'a>>, Error> {
+        self.list_entries_with(path, &ListOptions::default())
+    }
+
+    /// List a page of the children of a path, the kind and hash are only computed for the
+    /// entries that are returned
+    pub fn list_entries_with(
+        &self,
+        path: impl IntoPath,
+        options: &ListOptions,
+    ) -> Result<Vec<Entry<'a>>, Error> {
+        let path = path.into_path(&self.repo)?;
+
+        // irmin_tree_list has no offset or length parameters, so every step is read to sort
+        // them, but only the step name is fetched until the page is known
+        let arr = unsafe { irmin_tree_list(self.repo.ptr, self.ptr, path.ptr) };
+        check!(self.repo.ptr, arr);
+        let len = unsafe { irmin_path_array_length(self.repo.ptr, arr) };
+        let steps: Result<Vec<String>, Error> = (0..len)
+            .filter_map(|i| {
+                let p = unsafe { irmin_path_array_get(self.repo.ptr, arr, i) };
+                if p.is_null() {
+                    return None;
+                }
+                let p = Path {
+                    ptr: p,
+                    repo: self.repo.clone(),
+                };
+                Some(p.to_string())
+            })
+            .collect();
+        unsafe { irmin_path_array_free(arr) }
+        let mut steps = steps?;
+        steps.sort();
+
+        let start = match &options.start_after {
+            Some(s) => steps.partition_point(|step| step <= s),
+            None => 0,
+        };
+        let limit = options.limit.unwrap_or(usize::MAX);
+
+        let mut dest = Vec::new();
+        for step in steps.into_iter().skip(start + options.offset).take(limit) {
+            let p = path.append(&step)?;
+            let kind = if self.mem(&p)? {
+                Kind::Contents
+            } else {
+                Kind::Node
+            };
+            let hash = match self.find_tree(&p)? {
+                Some(tree) => Some(tree.hash()?),
+                None => None,
+            };
+            dest.push(Entry { step, kind, hash })
+        }
+        Ok(dest)
+    }
+}
+
+impl<'a, T: Contents> Store<'a, T> {
+    /// List the children of a path along with their kind and hash, sorted by step
+    pub fn list_entries(&self, path: impl IntoPath) -> Result<Vec<Entry<'a>>, Error> {
+        self.list_entries_with(path, &ListOptions::default())
+    }
+
+    /// List a page of the children of a path, see `Tree::list_entries_with`
+    pub fn list_entries_with(
+        &self,
+        path: impl IntoPath,
+        options: &ListOptions,
+    ) -> Result<Vec<Entry<'a>>, Error> {
+        match self.find_tree(path)? {
+            Some(tree) => tree.list_entries_with(&Path::empty(&self.repo)?, options),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl<'a, T: Contents> ReadOnlyStore<'a, T> {
+    /// List the children of a path along with their kind and hash, sorted by step
+    pub fn list_entries(&self, path: impl IntoPath) -> Result<Vec<Entry<'a>>, Error> {
+        self.list_entries_with(path, &ListOptions::default())
+    }
+
+    /// List a page of the children of a path, see `Tree::list_entries_with`
+    pub fn list_entries_with(
+        &self,
+        path: impl IntoPath,
+        options: &ListOptions,
+    ) -> Result<Vec<Entry<'a>>, Error> {
+        match self.find_tree(path)? {
+            Some(tree) => tree.list_entries_with(&Path::empty(self.repo())?, options),
+            None => Ok(Vec::new()),
+        }
+    }
+}
@@ -14,12 +14,13 @@ impl<'a> Drop for Metadata<'a> {
 
 impl<'a> Metadata<'a> {
     /// Default metadata value for the given repo
-    pub fn default<T: Contents>(repo: &'a Repo<T>) -> Result<Metadata<'a>, Error> {
+    pub fn default<T: Contents>(repo: impl Into<RepoRef<'a, T>>) -> Result<Metadata<'a>, Error> {
+        let repo = repo.into();
         let m = unsafe { irmin_metadata_default(repo.ptr) };
         check!(repo.ptr, m);
         Ok(Metadata {
             ptr: m,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
         })
     }
 }
@@ -0,0 +1,90 @@
+use crate::internal::*;
+
+/// Content-addressed access to the objects in a repo, independent of branches. See
+/// `Repo::objects`
+///
+/// libirmin can only write objects to the backend by creating a commit, so storing an object
+/// that isn't already in the repo also stores an empty commit with no parents that isn't
+/// referenced by any branch. Objects that are already stored are not written again. Unreferenced
+/// commits and objects are kept until they are removed by Irmin's garbage collector, which isn't
+/// available through libirmin, so objects that should be kept should also be reachable from a
+/// branch
+pub struct Objects<'a, T: Contents> {
+    repo: RepoRef<'a, T>,
+}
+
+impl<'a, T: Contents> Objects<'a, T> {
+    /// Access the objects in a repo
+    pub fn new(repo: impl Into<RepoRef<'a, T>>) -> Objects<'a, T> {
+        Objects { repo: repo.into() }
+    }
+
+    // Objects are written to the backend when a commit is created, the commit itself isn't
+    // referenced by any branch
+    fn persist(&self, tree: &Tree<T>) -> Result<Commit<'a, T>, Error> {
+        let info = Info::new(&self.repo, "", "")?;
+        Commit::new(&self.repo, [], tree, info)
+    }
+
+    /// Store a value, returning its hash
+    pub fn put_contents(&self, value: &T) -> Result<Hash<'a>, Error> {
+        let v = value.to_value_in(&self.repo.config.context)?;
+        let ptr = unsafe { irmin_contents_hash(self.repo.ptr, v.ptr as *mut _) };
+        check!(self.repo.ptr, ptr);
+        let hash = Hash {
+            ptr,
+            repo: UntypedRepo::new(&self.repo),
+        };
+        if T::of_hash(&self.repo, &hash).is_some() {
+            return Ok(hash);
+        }
+
+        let path = Path::new(&self.repo, &["contents"])?;
+        let mut tree = Tree::new(&self.repo)?;
+        tree.add(&path, value, None)?;
+        self.persist(&tree)?;
+        Ok(hash)
+    }
+
+    /// Store a tree and all of its children, returning the key of the root node
+    pub fn put_tree(&self, tree: &Tree<T>) -> Result<KindedKey<'a>, Error> {
+        if let Some(key) =
+            Tree::of_hash(&self.repo, &tree.hash()?)?.and_then(|t| t.key().transpose())
+        {
+            return key;
+        }
+
+        let commit = self.persist(tree)?;
+        match commit.tree()?.key()? {
+            Some(key) => Ok(key),
+            None => Err(Error::NullPtr),
+        }
+    }
+
+    /// Find a value using its hash
+    pub fn get_contents(&self, hash: &Hash) -> Option<T> {
+        T::of_hash(&self.repo, hash)
+    }
+
+    /// Find a value using its key
+    pub fn get_contents_by_key(&self, key: &ContentsKey) -> Option<T> {
+        T::of_key(&self.repo, key)
+    }
+
+    /// Find a tree using its hash
+    pub fn get_tree(&self, hash: &Hash) -> Result<Option<Tree<'a, T>>, Error> {
+        Tree::of_hash(&self.repo, hash)
+    }
+
+    /// Find a tree using its key
+    pub fn get_tree_by_key(&self, key: &KindedKey) -> Result<Option<Tree<'a, T>>, Error> {
+        Tree::of_key(&self.repo, key)
+    }
+}
+
+impl<T: Contents> Repo<T> {
+    /// Access the repo as a content-addressed object store
+    pub fn objects(&self) -> Objects<'_, T> {
+        Objects::new(self)
+    }
+}
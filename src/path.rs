@@ -19,21 +19,42 @@ impl<'a> PartialEq for Path<'a> {
 }
 
 impl<'a> Path<'a> {
-    /// Create a path from a string
-    pub fn from_str<T: Contents>(repo: &'a Repo<T>, s: impl AsRef<str>) -> Result<Path, Error> {
-        unsafe {
-            let s = s.as_ref();
-            let ptr = irmin_path_of_string(repo.ptr, s.as_ptr() as *mut _, s.len() as i64);
-            check!(repo.ptr, ptr);
-            Ok(Path {
-                ptr,
-                repo: UntypedRepo::new(repo),
-            })
-        }
+    /// Create a path from a string, segments are separated by `/` and escaped segments are
+    /// decoded, see `Path::escape_segment`
+    pub fn from_str<T: Contents>(
+        repo: impl Into<RepoRef<'a, T>>,
+        s: impl AsRef<str>,
+    ) -> Result<Path<'a>, Error> {
+        let repo = repo.into();
+        Path::parse(&UntypedRepo::new(&repo), s.as_ref())
+    }
+
+    /// Create a path from a slice, returns an error if any segment is invalid, see
+    /// `Path::validate_segment`
+    pub fn new<T: Contents>(
+        repo: impl Into<RepoRef<'a, T>>,
+        s: &[impl AsRef<str>],
+    ) -> Result<Path<'a>, Error> {
+        let repo = repo.into();
+        Path::of_segments(&UntypedRepo::new(&repo), s)
+    }
+
+    pub(crate) fn parse(repo: &UntypedRepo<'a>, s: &str) -> Result<Path<'a>, Error> {
+        let segments: Vec<_> = s
+            .split('/')
+            .filter(|x| !x.is_empty())
+            .map(Path::unescape_segment)
+            .collect();
+        Path::of_segments(repo, &segments)
     }
 
-    /// Create a path from a slice
-    pub fn new<T: Contents>(repo: &'a Repo<T>, s: &[impl AsRef<str>]) -> Result<Path<'a>, Error> {
+    pub(crate) fn of_segments(
+        repo: &UntypedRepo<'a>,
+        s: &[impl AsRef<str>],
+    ) -> Result<Path<'a>, Error> {
+        for x in s {
+            Path::validate_segment(x)?;
+        }
         let s: Vec<_> = s.iter().map(cstring).collect();
         let mut t: Vec<_> = s.iter().map(|x| x.as_ptr() as *mut u8).collect();
         t.push(std::ptr::null_mut());
@@ -41,17 +62,69 @@ impl<'a> Path<'a> {
         check!(repo.ptr, ptr);
         Ok(Path {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: repo.clone(),
         })
     }
 
+    /// Check that a segment can be used in a path, segments must be non-empty and can't
+    /// contain `/` or NUL characters
+    pub fn validate_segment(s: impl AsRef<str>) -> Result<(), Error> {
+        let s = s.as_ref();
+        if s.is_empty() {
+            return Err(Error::Parse(String::from("empty path segment")));
+        }
+        if s.contains('/') || s.contains('\0') {
+            return Err(Error::Parse(format!(
+                "invalid path segment, use Path::escape_segment: {s:?}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Escape `/` and `%` in a segment so it can be used as a single segment of a path, the
+    /// empty string is escaped as `%00`
+    pub fn escape_segment(s: impl AsRef<str>) -> String {
+        let s = s.as_ref();
+        if s.is_empty() {
+            return String::from("%00");
+        }
+        s.replace('%', "%25").replace('/', "%2F")
+    }
+
+    /// Decode a segment escaped using `Path::escape_segment`
+    pub fn unescape_segment(s: impl AsRef<str>) -> String {
+        let s = s.as_ref();
+        if s == "%00" {
+            return String::new();
+        }
+        let mut dest = String::with_capacity(s.len());
+        let mut rest = s;
+        while let Some(i) = rest.find('%') {
+            dest.push_str(&rest[..i]);
+            rest = &rest[i..];
+            match rest.get(..3) {
+                Some("%25") => dest.push('%'),
+                Some("%2F") | Some("%2f") => dest.push('/'),
+                _ => {
+                    dest.push('%');
+                    rest = &rest[1..];
+                    continue;
+                }
+            }
+            rest = &rest[3..];
+        }
+        dest.push_str(rest);
+        dest
+    }
+
     /// Create an empty path
-    pub fn empty<T: Contents>(repo: &'a Repo<T>) -> Result<Path<'a>, Error> {
+    pub fn empty<T: Contents>(repo: impl Into<RepoRef<'a, T>>) -> Result<Path<'a>, Error> {
+        let repo = repo.into();
         let ptr = unsafe { irmin_path_empty(repo.ptr as *mut _) };
         check!(repo.ptr, ptr);
         Ok(Path {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
         })
     }
 
@@ -99,4 +172,155 @@ impl<'a> Path<'a> {
         let s = IrminString::wrap(ptr);
         s.map(|x| x.into())
     }
+
+    /// Convert a path to a String with segments separated by `/`, segments are escaped using
+    /// `Path::escape_segment` so the result can be parsed using `Path::from_str`
+    pub fn display(&self) -> Result<String, Error> {
+        let segments: Vec<_> = self.segments()?.iter().map(Path::escape_segment).collect();
+        Ok(segments.join("/"))
+    }
+
+    /// Get the segments of a path
+    pub fn segments(&self) -> Result<Vec<String>, Error> {
+        let t = unsafe { irmin_type_path(self.repo.ptr) };
+        check!(self.repo.ptr, t);
+        let s = unsafe { irmin_value_to_json(t, self.ptr as *mut _) };
+        unsafe { irmin_type_free(t) }
+        let s = IrminString::wrap(s)?;
+        serde_json::from_slice(s.as_slice()).map_err(Error::from)
+    }
+
+    /// Returns true if the path starts with `prefix`
+    pub fn starts_with(&self, prefix: &Path) -> bool {
+        match (self.segments(), prefix.segments()) {
+            (Ok(a), Ok(b)) => a.starts_with(&b),
+            _ => false,
+        }
+    }
+
+    /// Remove `prefix` from the start of a path, returns `None` if the path doesn't start with
+    /// `prefix`
+    pub fn strip_prefix(&self, prefix: &Path) -> Result<Option<Path<'a>>, Error> {
+        let segments = self.segments()?;
+        let prefix = prefix.segments()?;
+        match segments.strip_prefix(prefix.as_slice()) {
+            Some(rest) => Path::of_segments(&self.repo, rest).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Either an owned or borrowed path, see `IntoPath`
+pub enum PathRef<'p> {
+    Owned(Path<'p>),
+    Borrowed(&'p Path<'p>),
+}
+
+impl<'p> std::ops::Deref for PathRef<'p> {
+    type Target = Path<'p>;
+
+    fn deref(&self) -> &Path<'p> {
+        match self {
+            PathRef::Owned(p) => p,
+            PathRef::Borrowed(p) => p,
+        }
+    }
+}
+
+/// Types that can be used as path arguments: `&Path`, strings separated by `/` and lists of
+/// segments
+pub trait IntoPath {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p;
+}
+
+impl<'b> IntoPath for &Path<'b> {
+    fn into_path<'p>(self, _repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Ok(PathRef::Borrowed(self))
+    }
+}
+
+impl<'b> IntoPath for Path<'b> {
+    fn into_path<'p>(self, _repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Ok(PathRef::Owned(self))
+    }
+}
+
+impl IntoPath for &str {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::parse(repo, self).map(PathRef::Owned)
+    }
+}
+
+impl IntoPath for &String {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::parse(repo, self).map(PathRef::Owned)
+    }
+}
+
+impl IntoPath for String {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::parse(repo, &self).map(PathRef::Owned)
+    }
+}
+
+impl<S: AsRef<str>> IntoPath for &[S] {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::of_segments(repo, self).map(PathRef::Owned)
+    }
+}
+
+impl<S: AsRef<str>, const N: usize> IntoPath for &[S; N] {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::of_segments(repo, self).map(PathRef::Owned)
+    }
+}
+
+impl<S: AsRef<str>, const N: usize> IntoPath for [S; N] {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::of_segments(repo, &self).map(PathRef::Owned)
+    }
+}
+
+impl<S: AsRef<str>> IntoPath for &Vec<S> {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::of_segments(repo, self).map(PathRef::Owned)
+    }
+}
+
+impl<S: AsRef<str>> IntoPath for Vec<S> {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::of_segments(repo, &self).map(PathRef::Owned)
+    }
 }
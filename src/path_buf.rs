@@ -0,0 +1,183 @@
+use crate::internal::*;
+
+use crate::path::PathRef;
+
+/// Owned path stored as a list of steps, independent of a repo. Use `PathBuf::to_path` or pass
+/// a `&PathBuf` to a method accepting `IntoPath` to convert it to a `Path`
+#[derive(
+    Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, std::hash::Hash, serde::Serialize,
+)]
+#[serde(transparent)]
+pub struct PathBuf {
+    steps: Vec<String>,
+}
+
+impl PathBuf {
+    /// Create an empty path
+    pub fn new() -> PathBuf {
+        PathBuf::default()
+    }
+
+    /// Create a path from a list of steps, returns an error if any step is invalid, see
+    /// `Path::validate_segment`
+    pub fn from_steps(
+        steps: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<PathBuf, Error> {
+        let mut path = PathBuf::new();
+        for step in steps {
+            path.push(step)?;
+        }
+        Ok(path)
+    }
+
+    /// Get the steps of a path
+    pub fn steps(&self) -> &[String] {
+        &self.steps
+    }
+
+    /// Number of steps
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns true if the path has no steps
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Get the last step of a path
+    pub fn last(&self) -> Option<&str> {
+        self.steps.last().map(|x| x.as_str())
+    }
+
+    /// Add a step to the end of the path
+    pub fn push(&mut self, step: impl Into<String>) -> Result<(), Error> {
+        let step = step.into();
+        Path::validate_segment(&step)?;
+        self.steps.push(step);
+        Ok(())
+    }
+
+    /// Remove the last step of the path
+    pub fn pop(&mut self) -> Option<String> {
+        self.steps.pop()
+    }
+
+    /// Create a new path with `step` appended
+    pub fn join(&self, step: impl Into<String>) -> Result<PathBuf, Error> {
+        let mut path = self.clone();
+        path.push(step)?;
+        Ok(path)
+    }
+
+    /// Create a new path by appending another path
+    pub fn join_path(&self, other: &PathBuf) -> PathBuf {
+        let mut path = self.clone();
+        path.steps.extend(other.steps.iter().cloned());
+        path
+    }
+
+    /// Get the parent path, returns `None` for the empty path
+    pub fn parent(&self) -> Option<PathBuf> {
+        let (_, steps) = self.steps.split_last()?;
+        Some(PathBuf {
+            steps: steps.to_vec(),
+        })
+    }
+
+    /// Returns true if the path starts with `prefix`
+    pub fn starts_with(&self, prefix: &PathBuf) -> bool {
+        self.steps.starts_with(&prefix.steps)
+    }
+
+    /// Remove `prefix` from the start of a path, returns `None` if the path doesn't start with
+    /// `prefix`
+    pub fn strip_prefix(&self, prefix: &PathBuf) -> Option<PathBuf> {
+        let steps = self.steps.strip_prefix(prefix.steps.as_slice())?;
+        Some(PathBuf {
+            steps: steps.to_vec(),
+        })
+    }
+
+    /// Returns true if the path matches a glob pattern, see `Glob`
+    pub fn matches(&self, glob: &Glob) -> bool {
+        glob.matches(self)
+    }
+
+    /// Convert to a `Path` for the given repo
+    pub fn to_path<'a, T: Contents>(
+        &self,
+        repo: impl Into<RepoRef<'a, T>>,
+    ) -> Result<Path<'a>, Error> {
+        Path::new(repo, &self.steps)
+    }
+}
+
+impl<'a> Path<'a> {
+    /// Convert to a `PathBuf`
+    pub fn to_path_buf(&self) -> Result<PathBuf, Error> {
+        self.segments().map(|steps| PathBuf { steps })
+    }
+}
+
+impl std::fmt::Display for PathBuf {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let steps: Vec<_> = self.steps.iter().map(Path::escape_segment).collect();
+        fmt.write_str(&steps.join("/"))
+    }
+}
+
+impl std::str::FromStr for PathBuf {
+    type Err = Error;
+
+    /// Parse a path with steps separated by `/`, escaped steps are decoded
+    fn from_str(s: &str) -> Result<PathBuf, Error> {
+        PathBuf::from_steps(
+            s.split('/')
+                .filter(|x| !x.is_empty())
+                .map(Path::unescape_segment),
+        )
+    }
+}
+
+impl TryFrom<Vec<String>> for PathBuf {
+    type Error = Error;
+
+    fn try_from(steps: Vec<String>) -> Result<PathBuf, Error> {
+        for step in &steps {
+            Path::validate_segment(step)?;
+        }
+        Ok(PathBuf { steps })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PathBuf {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<PathBuf, D::Error> {
+        let steps = Vec::<String>::deserialize(d)?;
+        PathBuf::try_from(steps).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<PathBuf> for Vec<String> {
+    fn from(path: PathBuf) -> Vec<String> {
+        path.steps
+    }
+}
+
+impl IntoPath for &PathBuf {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::of_segments(repo, &self.steps).map(PathRef::Owned)
+    }
+}
+
+impl IntoPath for PathBuf {
+    fn into_path<'p>(self, repo: &UntypedRepo<'p>) -> Result<PathRef<'p>, Error>
+    where
+        Self: 'p,
+    {
+        Path::of_segments(repo, &self.steps).map(PathRef::Owned)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::internal::*;
+
+/// Store handle that only allows reading, see `Store::snapshot` and `ReadOnlyRepo`
+pub struct ReadOnlyStore<'a, T: Contents> {
+    store: Store<'a, T>,
+}
+
+impl<'a, T: Contents> ReadOnlyStore<'a, T> {
+    /// Open the main branch of a store
+    pub fn new(repo: impl Into<RepoRef<'a, T>>) -> Result<ReadOnlyStore<'a, T>, Error> {
+        Store::new(repo).map(|store| ReadOnlyStore { store })
+    }
+
+    /// Specify the branch to open
+    pub fn of_branch(
+        repo: impl Into<RepoRef<'a, T>>,
+        branch: impl AsRef<str>,
+    ) -> Result<ReadOnlyStore<'a, T>, Error> {
+        Store::of_branch(repo, branch).map(|store| ReadOnlyStore { store })
+    }
+
+    /// Specify the commit to open
+    pub fn of_commit(
+        repo: impl Into<RepoRef<'a, T>>,
+        commit: &Commit<T>,
+    ) -> Result<ReadOnlyStore<'a, T>, Error> {
+        Store::of_commit(repo, commit).map(|store| ReadOnlyStore { store })
+    }
+
+    // Not public, a `RepoRef` could be used to open a writable `Store`
+    pub(crate) fn repo(&self) -> &RepoRef<'a, T> {
+        &self.store.repo
+    }
+
+    /// Find the value associated with the given path
+    pub fn find(&self, path: impl IntoPath) -> Result<Option<T>, Error> {
+        self.store.find(path)
+    }
+
+    /// Find the tree associated with the given path
+    pub fn find_tree(&self, path: impl IntoPath) -> Result<Option<Tree<'a, T>>, Error> {
+        self.store.find_tree(path)
+    }
+
+    /// Check for the existence of a value at the given path
+    pub fn mem(&self, path: impl IntoPath) -> Result<bool, Error> {
+        self.store.mem(path)
+    }
+
+    /// Check for the existence of a tree at the given path
+    pub fn mem_tree(&self, path: impl IntoPath) -> Result<bool, Error> {
+        self.store.mem_tree(path)
+    }
+
+    /// List paths
+    pub fn list(&self, path: impl IntoPath) -> Result<Vec<Path<'a>>, Error> {
+        self.store.list(path)
+    }
+
+    /// Get current head commit
+    pub fn head(&self) -> Result<Option<Commit<'a, T>>, Error> {
+        self.store.head()
+    }
+}
+
+impl<'a, T: Contents> Store<'a, T> {
+    /// Get a read-only view of the current head commit, returns `None` if the branch has no
+    /// commits. Later updates to the branch are not visible through the snapshot
+    pub fn snapshot(&self) -> Result<Option<ReadOnlyStore<'a, T>>, Error> {
+        match self.head()? {
+            Some(head) => ReadOnlyStore::of_commit(&self.repo, &head).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Repo opened in read-only mode, see `Repo::open_readonly`. Only `ReadOnlyStore` handles can
+/// be created from it:
+///
+/// ```compile_fail
+/// # use irmin::*;
+/// # fn main() -> Result<(), Error> {
+/// let repo = Repo::open_readonly(Config::<String>::pack(None)?)?;
+/// let store = Store::new(&repo)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReadOnlyRepo<T: Contents> {
+    repo: Repo<T>,
+}
+
+impl<T: Contents> ReadOnlyRepo<T> {
+    /// Open the main branch
+    pub fn main(&self) -> Result<ReadOnlyStore<'_, T>, Error> {
+        ReadOnlyStore::new(&self.repo)
+    }
+
+    /// Open a branch
+    pub fn of_branch(&self, branch: impl AsRef<str>) -> Result<ReadOnlyStore<'_, T>, Error> {
+        ReadOnlyStore::of_branch(&self.repo, branch)
+    }
+
+    /// Open a commit
+    pub fn of_commit(&self, commit: &Commit<T>) -> Result<ReadOnlyStore<'_, T>, Error> {
+        ReadOnlyStore::of_commit(&self.repo, commit)
+    }
+
+    /// Get a list of all branches
+    pub fn branches(&self) -> Result<Vec<IrminString>, Error> {
+        self.repo.branches()
+    }
+
+    /// Create a new path
+    pub fn path(&self, s: &[impl AsRef<str>]) -> Result<Path<'_>, Error> {
+        self.repo.path(s)
+    }
+
+    /// Parse a hash
+    pub fn hash(&self, s: impl AsRef<str>) -> Result<Hash<'_>, Error> {
+        Hash::of_string(&self.repo, s)
+    }
+
+    /// Find the commit with the given hash
+    pub fn commit_of_hash(&self, hash: &Hash) -> Result<Option<Commit<'_, T>>, Error> {
+        Commit::of_hash(&self.repo, hash)
+    }
+}
+
+impl<T: Contents> Repo<T> {
+    /// Create a new repo from the given config with read-only mode enabled, only backends with a
+    /// `readonly` option (pack and tezos) are supported
+    pub fn open_readonly(mut config: Config<T>) -> Result<ReadOnlyRepo<T>, Error> {
+        let readonly = Value::bool(true)?;
+        if !config.set("readonly", &readonly.ty, &readonly) {
+            return Err(Error::Config(String::from(
+                "readonly is not supported by this backend",
+            )));
+        }
+        Repo::new(config).map(|repo| ReadOnlyRepo { repo })
+    }
+}
@@ -13,28 +13,33 @@ impl<'a> Remote<'a> {
         check!(store.repo.ptr, ptr);
         Ok(Remote {
             ptr,
-            repo: UntypedRepo::new(store.repo),
+            repo: UntypedRepo::new(&store.repo),
         })
     }
 
     /// Remote from URL
-    pub fn url<T: Contents>(repo: &'a Repo<T>, s: impl AsRef<str>) -> Result<Remote<'a>, Error> {
+    pub fn url<T: Contents>(
+        repo: impl Into<RepoRef<'a, T>>,
+        s: impl AsRef<str>,
+    ) -> Result<Remote<'a>, Error> {
+        let repo = repo.into();
         let mut s = cstring(s.as_ref());
         let ptr = unsafe { irmin_remote(repo.ptr, s.as_mut_ptr() as *mut _) };
         check!(repo.ptr, ptr);
         Ok(Remote {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
         })
     }
 
     /// Remote from URL with basic auth
     pub fn url_with_auth<T: Contents>(
-        repo: &'a Repo<T>,
+        repo: impl Into<RepoRef<'a, T>>,
         s: impl AsRef<str>,
         user: impl AsRef<str>,
         token: impl AsRef<str>,
     ) -> Result<Remote<'a>, Error> {
+        let repo = repo.into();
         let mut s = cstring(s.as_ref());
         let mut user = cstring(user.as_ref());
         let mut token = cstring(token.as_ref());
@@ -49,7 +54,7 @@ impl<'a> Remote<'a> {
         check!(repo.ptr, ptr);
         Ok(Remote {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
         })
     }
 }
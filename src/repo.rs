@@ -18,20 +18,16 @@ impl<T: Contents> Repo<T> {
         }
     }
 
+    /// Create a new repo that can be shared between handles, handles created from a shared repo
+    /// using `Store::new(&repo)`, `Path::new(&repo, ..)`, etc. keep it alive and have a
+    /// `'static` lifetime
+    pub fn shared(config: Config<T>) -> Result<std::sync::Arc<Repo<T>>, Error> {
+        Repo::new(config).map(std::sync::Arc::new)
+    }
+
     /// Get a list of all branches
     pub fn branches(&self) -> Result<Vec<IrminString>, Error> {
-        let b = unsafe { irmin_repo_branches(self.ptr) };
-        check!(self.ptr, b);
-        let mut dest = Vec::new();
-        let n = unsafe { irmin_branch_array_length(self.ptr, b) };
-        for i in 0..n {
-            let p = unsafe { irmin_branch_array_get(self.ptr, b, i) };
-            if let Ok(s) = IrminString::wrap(p) {
-                dest.push(s);
-            }
-        }
-        unsafe { irmin_branch_array_free(b) };
-        Ok(dest)
+        branches(self.ptr)
     }
 
     /// Create a new path
@@ -40,12 +36,15 @@ impl<T: Contents> Repo<T> {
     }
 
     /// Create a new commit
-    pub fn commit<'a>(
+    pub fn commit<'a, 'b>(
         &'a self,
-        parents: impl AsRef<[&'a Commit<'a>]>,
+        parents: impl AsRef<[&'b Commit<'b, T>]>,
         tree: &Tree<T>,
         info: Info,
-    ) -> Result<Commit<'a>, Error> {
+    ) -> Result<Commit<'a, T>, Error>
+    where
+        T: 'b,
+    {
         Commit::new(self, parents, tree, info)
     }
 
@@ -65,3 +64,18 @@ impl<T: Contents> Drop for Repo<T> {
         unsafe { irmin_repo_free(self.ptr) }
     }
 }
+
+pub(crate) fn branches(repo: *mut IrminRepo) -> Result<Vec<IrminString>, Error> {
+    let b = unsafe { irmin_repo_branches(repo) };
+    check!(repo, b);
+    let mut dest = Vec::new();
+    let n = unsafe { irmin_branch_array_length(repo, b) };
+    for i in 0..n {
+        let p = unsafe { irmin_branch_array_get(repo, b, i) };
+        if let Ok(s) = IrminString::wrap(p) {
+            dest.push(s);
+        }
+    }
+    unsafe { irmin_branch_array_free(b) };
+    Ok(dest)
+}
@@ -3,12 +3,13 @@ use crate::internal::*;
 /// Wrapper around Irmin.S
 pub struct Store<'a, T: Contents> {
     pub ptr: *mut Irmin,
-    pub repo: &'a Repo<T>,
+    pub repo: RepoRef<'a, T>,
 }
 
 impl<'a, T: Contents> Store<'a, T> {
     /// Open the main branch of a store
-    pub fn new(repo: &'a Repo<T>) -> Result<Store<'a, T>, Error> {
+    pub fn new(repo: impl Into<RepoRef<'a, T>>) -> Result<Store<'a, T>, Error> {
+        let repo = repo.into();
         unsafe {
             let ptr = irmin_main(repo.ptr);
             check!(repo.ptr, ptr);
@@ -17,7 +18,11 @@ impl<'a, T: Contents> Store<'a, T> {
     }
 
     /// Specify the branch to open
-    pub fn of_branch(repo: &'a Repo<T>, branch: impl AsRef<str>) -> Result<Store<'a, T>, Error> {
+    pub fn of_branch(
+        repo: impl Into<RepoRef<'a, T>>,
+        branch: impl AsRef<str>,
+    ) -> Result<Store<'a, T>, Error> {
+        let repo = repo.into();
         let branch = cstring(branch);
         unsafe {
             let ptr = irmin_of_branch(repo.ptr, branch.as_ptr() as *mut _);
@@ -27,7 +32,11 @@ impl<'a, T: Contents> Store<'a, T> {
     }
 
     /// Specify the commit to open
-    pub fn of_commit(repo: &'a Repo<T>, commit: &Commit) -> Result<Store<'a, T>, Error> {
+    pub fn of_commit(
+        repo: impl Into<RepoRef<'a, T>>,
+        commit: &Commit<T>,
+    ) -> Result<Store<'a, T>, Error> {
+        let repo = repo.into();
         unsafe {
             let ptr = irmin_of_commit(repo.ptr, commit.ptr);
             check!(repo.ptr, ptr);
@@ -36,8 +45,9 @@ impl<'a, T: Contents> Store<'a, T> {
     }
 
     /// Set a value, creating a new commit
-    pub fn set(&mut self, path: &Path, value: &T, info: Info) -> Result<bool, Error> {
-        let value = value.to_value()?;
+    pub fn set(&mut self, path: impl IntoPath, value: &T, info: Info) -> Result<bool, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
+        let value = value.to_value_in(&self.repo.config.context)?;
         unsafe {
             let r = irmin_set(self.ptr, path.ptr, value.ptr as *mut _, info.ptr);
             check!(self.repo.ptr, r, false);
@@ -48,17 +58,18 @@ impl<'a, T: Contents> Store<'a, T> {
     /// Set a value if `old` matches the current value
     pub fn test_and_set(
         &mut self,
-        path: &Path,
+        path: impl IntoPath,
         old: Option<&T>,
         value: Option<&T>,
         info: Info,
     ) -> Result<bool, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
         let old = match old {
-            Some(value) => Some(value.to_value()?),
+            Some(value) => Some(value.to_value_in(&self.repo.config.context)?),
             None => None,
         };
         let value = match value {
-            Some(value) => Some(value.to_value()?),
+            Some(value) => Some(value.to_value_in(&self.repo.config.context)?),
             None => None,
         };
         unsafe {
@@ -78,7 +89,13 @@ impl<'a, T: Contents> Store<'a, T> {
     }
 
     /// Set a tree, creating a new commit
-    pub fn set_tree(&mut self, path: &Path, tree: &Tree<T>, info: Info) -> Result<bool, Error> {
+    pub fn set_tree(
+        &mut self,
+        path: impl IntoPath,
+        tree: &Tree<T>,
+        info: Info,
+    ) -> Result<bool, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
         unsafe {
             let r = irmin_set_tree(self.ptr, path.ptr, tree.ptr, info.ptr);
             check!(self.repo.ptr, r, false);
@@ -89,11 +106,12 @@ impl<'a, T: Contents> Store<'a, T> {
     /// Set a tree if `old` matches the current tree
     pub fn test_and_set_tree(
         &mut self,
-        path: &Path,
+        path: impl IntoPath,
         old: Option<&Tree<T>>,
         tree: Option<&Tree<T>>,
         info: Info,
     ) -> Result<bool, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
         unsafe {
             let r = irmin_test_and_set_tree(
                 self.ptr,
@@ -108,7 +126,8 @@ impl<'a, T: Contents> Store<'a, T> {
     }
 
     /// Find the value associated with the given path
-    pub fn find(&self, path: &Path) -> Result<Option<T>, Error> {
+    pub fn find(&self, path: impl IntoPath) -> Result<Option<T>, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
         let r = unsafe { irmin_find(self.ptr, path.ptr) };
         check_opt!(self.repo.ptr, r);
         let ty = T::ty()?;
@@ -116,18 +135,19 @@ impl<'a, T: Contents> Store<'a, T> {
             ptr: r as *mut _,
             ty,
         };
-        let v = T::from_value(&v)?;
+        let v = T::from_value_in(&v, &self.repo.config.context)?;
         Ok(Some(v))
     }
 
     /// Find the tree associated with the given path
-    pub fn find_tree(&self, path: &Path) -> Result<Option<Tree<T>>, Error> {
+    pub fn find_tree(&self, path: impl IntoPath) -> Result<Option<Tree<'a, T>>, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
         unsafe {
             let ptr = irmin_find_tree(self.ptr, path.ptr);
             check_opt!(self.repo.ptr, ptr);
             let x = Tree {
                 ptr,
-                repo: UntypedRepo::new(self.repo),
+                repo: UntypedRepo::new(&self.repo),
                 _t: std::marker::PhantomData,
             };
             Ok(Some(x))
@@ -135,37 +155,47 @@ impl<'a, T: Contents> Store<'a, T> {
     }
 
     /// Check for the existence of a value at the given path
-    pub fn mem(&self, path: &Path) -> bool {
-        unsafe { irmin_mem(self.ptr, path.ptr) }
+    pub fn mem(&self, path: impl IntoPath) -> Result<bool, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
+        let x = unsafe { irmin_mem(self.ptr, path.ptr) };
+        check!(self.repo.ptr, x, false);
+        Ok(x)
     }
 
     /// Check for the existence of a tree at the given path
-    pub fn mem_tree(&self, path: &Path) -> bool {
-        unsafe { irmin_mem_tree(self.ptr, path.ptr) }
+    pub fn mem_tree(&self, path: impl IntoPath) -> Result<bool, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
+        let x = unsafe { irmin_mem_tree(self.ptr, path.ptr) };
+        check!(self.repo.ptr, x, false);
+        Ok(x)
     }
 
     /// Remove the tree or value associated with the given path
-    pub fn remove(&mut self, path: &Path, info: Info) -> bool {
-        unsafe { irmin_remove(self.ptr, path.ptr, info.ptr) }
+    pub fn remove(&mut self, path: impl IntoPath, info: Info) -> Result<bool, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
+        let x = unsafe { irmin_remove(self.ptr, path.ptr, info.ptr) };
+        check!(self.repo.ptr, x, false);
+        Ok(x)
     }
 
     /// Get current head commit
-    pub fn head(&self) -> Result<Option<Commit<'a>>, Error> {
+    pub fn head(&self) -> Result<Option<Commit<'a, T>>, Error> {
         let ptr = unsafe { irmin_get_head(self.ptr) };
         check_opt!(self.repo.ptr, ptr);
         Ok(Some(Commit {
             ptr,
-            repo: UntypedRepo::new(self.repo),
+            repo: UntypedRepo::new(&self.repo),
+            _t: std::marker::PhantomData,
         }))
     }
 
     /// Set head commit
-    pub fn set_head(&mut self, c: &Commit) {
+    pub fn set_head(&mut self, c: &Commit<T>) {
         unsafe { irmin_set_head(self.ptr, c.ptr) }
     }
 
     /// Update current branch to the specified commit
-    pub fn fast_forward(&mut self, c: &Commit) -> bool {
+    pub fn fast_forward(&mut self, c: &Commit<T>) -> bool {
         unsafe { irmin_fast_forward(self.ptr, c.ptr) }
     }
 
@@ -182,7 +212,7 @@ impl<'a, T: Contents> Store<'a, T> {
     }
 
     /// Merge with another commit
-    pub fn merge_with_commit(&mut self, commit: &Commit, info: Info) -> Result<bool, Error> {
+    pub fn merge_with_commit(&mut self, commit: &Commit<T>, info: Info) -> Result<bool, Error> {
         let r = unsafe { irmin_merge_with_commit(self.ptr, commit.ptr, info.ptr) };
         check!(self.repo.ptr, r, false);
         Ok(r)
@@ -196,7 +226,8 @@ impl<'a, T: Contents> Store<'a, T> {
     }
 
     /// List paths
-    pub fn list(&self, path: &Path) -> Result<Vec<Path>, Error> {
+    pub fn list(&self, path: impl IntoPath) -> Result<Vec<Path<'a>>, Error> {
+        let path = path.into_path(&UntypedRepo::new(&self.repo))?;
         let p = unsafe { irmin_list(self.ptr, path.ptr) };
         check!(self.repo.ptr, p);
         let len = unsafe { irmin_path_array_length(self.repo.ptr, p) };
@@ -208,7 +239,7 @@ impl<'a, T: Contents> Store<'a, T> {
             }
             dest.push(Path {
                 ptr: path,
-                repo: UntypedRepo::new(self.repo),
+                repo: UntypedRepo::new(&self.repo),
             })
         }
 
@@ -224,7 +255,7 @@ impl<'a, T: Contents> Store<'a, T> {
         remote: &Remote,
         depth: Option<i32>,
         info: Option<&Info>,
-    ) -> Result<Commit, Error> {
+    ) -> Result<Commit<'a, T>, Error> {
         let info = match info {
             Some(i) => i.ptr as *mut _,
             None => std::ptr::null_mut(),
@@ -234,29 +265,32 @@ impl<'a, T: Contents> Store<'a, T> {
         check!(self.repo.ptr, c);
         Ok(Commit {
             ptr: c,
-            repo: UntypedRepo::new(self.repo),
+            repo: UntypedRepo::new(&self.repo),
+            _t: std::marker::PhantomData,
         })
     }
 
     /// Fetch data from a remote repository
-    pub fn fetch(&mut self, remote: &Remote, depth: Option<i32>) -> Result<Commit, Error> {
+    pub fn fetch(&mut self, remote: &Remote, depth: Option<i32>) -> Result<Commit<'a, T>, Error> {
         let depth = depth.unwrap_or(-1);
         let c = unsafe { irmin_fetch(self.ptr, depth, remote.ptr) };
         check!(self.repo.ptr, c);
         Ok(Commit {
             ptr: c,
-            repo: UntypedRepo::new(self.repo),
+            repo: UntypedRepo::new(&self.repo),
+            _t: std::marker::PhantomData,
         })
     }
 
     /// Push to a remote repository
-    pub fn push(&mut self, remote: &Remote, depth: Option<i32>) -> Result<Commit, Error> {
+    pub fn push(&mut self, remote: &Remote, depth: Option<i32>) -> Result<Commit<'a, T>, Error> {
         let depth = depth.unwrap_or(-1);
         let c = unsafe { irmin_push(self.ptr, depth, remote.ptr) };
         check!(self.repo.ptr, c);
         Ok(Commit {
             ptr: c,
-            repo: UntypedRepo::new(self.repo),
+            repo: UntypedRepo::new(&self.repo),
+            _t: std::marker::PhantomData,
         })
     }
 }
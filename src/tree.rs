@@ -21,20 +21,53 @@ impl<'a, T: Contents> PartialEq for Tree<'a, T> {
 
 impl<'a, T: Contents> Tree<'a, T> {
     /// Create an empty tree
-    pub fn new(repo: &'a Repo<T>) -> Result<Tree<'a, T>, Error> {
+    pub fn new(repo: impl Into<RepoRef<'a, T>>) -> Result<Tree<'a, T>, Error> {
+        let repo = repo.into();
         unsafe {
             let ptr = irmin_tree_new(repo.ptr);
             check!(repo.ptr, ptr);
             Ok(Tree {
                 ptr,
-                repo: UntypedRepo::new(repo),
+                repo: UntypedRepo::new(&repo),
                 _t: std::marker::PhantomData,
             })
         }
     }
 
+    /// Create a tree containing a single value
+    pub fn from_contents(
+        repo: impl Into<RepoRef<'a, T>>,
+        value: &T,
+        metadata: Option<&Metadata>,
+    ) -> Result<Tree<'a, T>, Error> {
+        let repo = repo.into();
+        let value = value.to_value_in(&repo.config.context)?;
+        let meta = match metadata {
+            Some(m) => m.ptr,
+            None => std::ptr::null_mut(),
+        };
+        let ptr = unsafe { irmin_tree_of_contents(repo.ptr, value.ptr as *mut _, meta) };
+        check!(repo.ptr, ptr);
+        Ok(Tree {
+            ptr,
+            repo: UntypedRepo::new(&repo),
+            _t: std::marker::PhantomData,
+        })
+    }
+
+    /// Copy a tree, modifications to the copy don't affect the original
+    pub fn try_clone(&self) -> Result<Tree<'a, T>, Error> {
+        let ptr = unsafe { irmin_tree_clone(self.repo.ptr, self.ptr) };
+        check!(self.repo.ptr, ptr);
+        Ok(Tree {
+            ptr,
+            repo: self.repo.clone(),
+            _t: std::marker::PhantomData,
+        })
+    }
+
     /// Compute the hash of a tree
-    pub fn hash(&self) -> Result<Hash, Error> {
+    pub fn hash(&self) -> Result<Hash<'a>, Error> {
         let h = unsafe { irmin_tree_hash(self.repo.ptr, self.ptr) };
         check!(self.repo.ptr, h);
         Ok(Hash {
@@ -44,7 +77,7 @@ impl<'a, T: Contents> Tree<'a, T> {
     }
 
     ///
-    pub fn key(&self) -> Result<Option<KindedKey>, Error> {
+    pub fn key(&self) -> Result<Option<KindedKey<'a>>, Error> {
         let h = unsafe { irmin_tree_key(self.repo.ptr, self.ptr) };
         check_opt!(self.repo.ptr, h);
         Ok(Some(KindedKey {
@@ -53,22 +86,30 @@ impl<'a, T: Contents> Tree<'a, T> {
         }))
     }
 
-    pub fn of_hash(repo: &'a Repo<T>, h: &Hash) -> Result<Option<Tree<'a, T>>, Error> {
+    pub fn of_hash(
+        repo: impl Into<RepoRef<'a, T>>,
+        h: &Hash,
+    ) -> Result<Option<Tree<'a, T>>, Error> {
+        let repo = repo.into();
         let ptr = unsafe { irmin_tree_of_hash(repo.ptr, h.ptr) };
         check_opt!(repo.ptr, ptr);
         Ok(Some(Tree {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
             _t: std::marker::PhantomData,
         }))
     }
 
-    pub fn of_key(repo: &'a Repo<T>, k: &KindedKey) -> Result<Option<Tree<'a, T>>, Error> {
+    pub fn of_key(
+        repo: impl Into<RepoRef<'a, T>>,
+        k: &KindedKey,
+    ) -> Result<Option<Tree<'a, T>>, Error> {
+        let repo = repo.into();
         let ptr = unsafe { irmin_tree_of_key(repo.ptr, k.ptr) };
         check_opt!(repo.ptr, ptr);
         Ok(Some(Tree {
             ptr,
-            repo: UntypedRepo::new(repo),
+            repo: UntypedRepo::new(&repo),
             _t: std::marker::PhantomData,
         }))
     }
@@ -76,12 +117,13 @@ impl<'a, T: Contents> Tree<'a, T> {
     /// Update the tree with a value at the specified path
     pub fn add(
         &mut self,
-        path: &Path,
+        path: impl IntoPath,
         value: &T,
         metadata: Option<&Metadata>,
     ) -> Result<(), Error> {
+        let path = path.into_path(&self.repo)?;
         let x = unsafe {
-            let value = value.to_value()?;
+            let value = value.to_value_in(&self.repo.context)?;
             let meta = match metadata {
                 Some(m) => m.ptr,
                 None => std::ptr::null_mut(),
@@ -93,49 +135,56 @@ impl<'a, T: Contents> Tree<'a, T> {
     }
 
     /// Update the tree with a tree at the specified path
-    pub fn add_tree(&mut self, path: &Path, tree: &Tree<T>) -> Result<(), Error> {
+    pub fn add_tree(&mut self, path: impl IntoPath, tree: &Tree<T>) -> Result<(), Error> {
+        let path = path.into_path(&self.repo)?;
         let x = unsafe { irmin_tree_add_tree(self.repo.ptr, self.ptr, path.ptr, tree.ptr) };
         check!(self.repo.ptr, x, false);
         Ok(())
     }
 
     /// Check for the existence of a value at the given path
-    pub fn mem(&self, path: &Path) -> bool {
-        unsafe { irmin_tree_mem(self.repo.ptr, self.ptr, path.ptr) }
+    pub fn mem(&self, path: impl IntoPath) -> Result<bool, Error> {
+        let path = path.into_path(&self.repo)?;
+        let x = unsafe { irmin_tree_mem(self.repo.ptr, self.ptr, path.ptr) };
+        check!(self.repo.ptr, x, false);
+        Ok(x)
     }
 
     /// Check for the existence of a tree at the given path
-    pub fn mem_tree(&self, path: &Path) -> bool {
-        unsafe { irmin_tree_mem_tree(self.repo.ptr, self.ptr, path.ptr) }
+    pub fn mem_tree(&self, path: impl IntoPath) -> Result<bool, Error> {
+        let path = path.into_path(&self.repo)?;
+        let x = unsafe { irmin_tree_mem_tree(self.repo.ptr, self.ptr, path.ptr) };
+        check!(self.repo.ptr, x, false);
+        Ok(x)
     }
 
     /// Remove any bindings for the given path
-    pub fn remove(&mut self, path: &Path) -> Result<(), Error> {
+    pub fn remove(&mut self, path: impl IntoPath) -> Result<(), Error> {
+        let path = path.into_path(&self.repo)?;
         let x = unsafe { irmin_tree_remove(self.repo.ptr, self.ptr, path.ptr) };
         check!(self.repo.ptr, x, false);
         Ok(())
     }
 
     /// Find a value associated with a path
-    pub fn find(&self, path: &Path) -> Result<Option<T>, Error> {
+    pub fn find(&self, path: impl IntoPath) -> Result<Option<T>, Error> {
+        let path = path.into_path(&self.repo)?;
         unsafe {
             let ptr = irmin_tree_find(self.repo.ptr, self.ptr, path.ptr);
-            check!(self.repo.ptr, ptr);
-            if ptr.is_null() {
-                return Ok(None);
-            }
+            check_opt!(self.repo.ptr, ptr);
             let ty = T::ty()?;
             let x = Value {
                 ptr: ptr as *mut _,
                 ty,
             };
-            let value = T::from_value(&x)?;
+            let value = T::from_value_in(&x, &self.repo.context)?;
             Ok(Some(value))
         }
     }
 
     /// Find a tree associated with a path
-    pub fn find_tree(&self, path: &Path) -> Result<Option<Tree<T>>, Error> {
+    pub fn find_tree(&self, path: impl IntoPath) -> Result<Option<Tree<'a, T>>, Error> {
+        let path = path.into_path(&self.repo)?;
         unsafe {
             let ptr = irmin_tree_find_tree(self.repo.ptr, self.ptr, path.ptr);
             check_opt!(self.repo.ptr, ptr);
@@ -149,7 +198,8 @@ impl<'a, T: Contents> Tree<'a, T> {
     }
 
     /// List paths
-    pub fn list(&self, path: &Path) -> Result<Vec<Path>, Error> {
+    pub fn list(&self, path: impl IntoPath) -> Result<Vec<Path<'a>>, Error> {
+        let path = path.into_path(&self.repo)?;
         let p = unsafe { irmin_tree_list(self.repo.ptr, self.ptr, path.ptr) };
         check!(self.repo.ptr, p);
         let len = unsafe { irmin_path_array_length(self.repo.ptr, p) };
@@ -170,3 +220,28 @@ impl<'a, T: Contents> Tree<'a, T> {
         Ok(dest)
     }
 }
+
+/// Build a tree from a list of paths and values, values are converted to the content type of
+/// the repo using `Into`
+///
+/// ```ignore
+/// let tree = irmin::tree!(&repo, {
+///     "a/b" => "c",
+///     "a/d" => "e",
+/// })?;
+/// ```
+#[macro_export]
+macro_rules! tree {
+    ($repo:expr, { $($path:expr => $value:expr),* $(,)? }) => {
+        (|| {
+            let repo = $crate::RepoRef::from($repo);
+            #[allow(unused_mut)]
+            let mut tree = $crate::Tree::new(&repo)?;
+            $(
+                let path = $crate::Path::from_str(&repo, $path)?;
+                tree.add(&path, &($value).into(), None)?;
+            )*
+            Ok::<_, $crate::Error>(tree)
+        })()
+    };
+}
@@ -73,6 +73,69 @@ impl Type {
         Ok(Type { ptr })
     }
 
+    /// Irmin.Type.option
+    pub fn option(t: &Type) -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_option(t.ptr) };
+        if ptr.is_null() {
+            return Err(Error::NullPtr);
+        };
+        Ok(Type { ptr })
+    }
+
+    /// Irmin.Type.unit
+    pub fn unit() -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_unit() };
+        if ptr.is_null() {
+            return Err(Error::NullPtr);
+        };
+        Ok(Type { ptr })
+    }
+
+    /// Irmin.Type.bytes
+    pub fn bytes() -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_bytes() };
+        if ptr.is_null() {
+            return Err(Error::NullPtr);
+        };
+        Ok(Type { ptr })
+    }
+
+    /// Irmin.Type.list
+    pub fn list(t: &Type) -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_list(t.ptr) };
+        if ptr.is_null() {
+            return Err(Error::NullPtr);
+        };
+        Ok(Type { ptr })
+    }
+
+    /// Irmin.Type.array
+    pub fn array(t: &Type) -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_array(t.ptr) };
+        if ptr.is_null() {
+            return Err(Error::NullPtr);
+        };
+        Ok(Type { ptr })
+    }
+
+    /// Irmin.Type.pair
+    pub fn pair(a: &Type, b: &Type) -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_pair(a.ptr, b.ptr) };
+        if ptr.is_null() {
+            return Err(Error::NullPtr);
+        };
+        Ok(Type { ptr })
+    }
+
+    /// Irmin.Type.triple
+    pub fn triple(a: &Type, b: &Type, c: &Type) -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_triple(a.ptr, b.ptr, c.ptr) };
+        if ptr.is_null() {
+            return Err(Error::NullPtr);
+        };
+        Ok(Type { ptr })
+    }
+
     /// The path type for a Repo
     pub fn path<T: Contents>(repo: &Repo<T>) -> Result<Type, Error> {
         let ptr = unsafe { irmin_type_path(repo.ptr) };
@@ -100,6 +163,20 @@ impl Type {
         Ok(Type { ptr })
     }
 
+    /// The contents key type for a Repo
+    pub fn contents_key<T: Contents>(repo: &Repo<T>) -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_contents_key(repo.ptr) };
+        check!(repo.ptr, ptr);
+        Ok(Type { ptr })
+    }
+
+    /// The node key type for a Repo
+    pub fn node_key<T: Contents>(repo: &Repo<T>) -> Result<Type, Error> {
+        let ptr = unsafe { irmin_type_node_key(repo.ptr) };
+        check!(repo.ptr, ptr);
+        Ok(Type { ptr })
+    }
+
     pub fn kinded_key<T: Contents>(repo: &Repo<T>) -> Result<Type, Error> {
         let ptr = unsafe { irmin_type_kinded_key(repo.ptr) };
         check!(repo.ptr, ptr);
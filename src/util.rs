@@ -1,22 +1,180 @@
 use crate::internal::*;
 
+use std::sync::Arc;
+
 pub(crate) fn cstring(s: impl AsRef<str>) -> String {
     let mut s = s.as_ref().to_string();
     s.push('\0');
     s
 }
 
+pub(crate) fn to_hex(b: &[u8]) -> String {
+    let mut s = String::with_capacity(b.len() * 2);
+    for x in b {
+        s.push_str(&format!("{x:02x}"));
+    }
+    s
+}
+
+pub(crate) fn of_hex(s: &str) -> Result<Vec<u8>, Error> {
+    if !s.len().is_multiple_of(2) {
+        return Err(Error::Parse(format!("invalid hex string: {s}")));
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|x| u8::from_str_radix(x, 16).ok())
+                .ok_or_else(|| Error::Parse(format!("invalid hex string: {s}")))
+        })
+        .collect()
+}
+
+/// Reference to a `Repo`, either borrowed or shared using `Arc`. Handles created from a shared
+/// repo keep it alive, so they have a `'static` lifetime and can be stored in long-lived structs.
+/// Like every other handle they are not `Send`, since libirmin can only be used from one thread
+pub struct RepoRef<'a, T: Contents> {
+    repo: *const Repo<T>,
+    owner: Option<Arc<dyn std::any::Any>>,
+    _t: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, T: Contents> RepoRef<'a, T> {
+    /// Returns true when the repo is shared using `Arc`
+    pub fn is_shared(&self) -> bool {
+        self.owner.is_some()
+    }
+}
+
+impl<'a, T: Contents> Clone for RepoRef<'a, T> {
+    fn clone(&self) -> Self {
+        RepoRef {
+            repo: self.repo,
+            owner: self.owner.clone(),
+            _t: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Contents> std::ops::Deref for RepoRef<'a, T> {
+    type Target = Repo<T>;
+
+    fn deref(&self) -> &Repo<T> {
+        // The repo is either borrowed for `'a` or kept alive by `owner`
+        unsafe { &*self.repo }
+    }
+}
+
+impl<'a, T: Contents> From<&'a Repo<T>> for RepoRef<'a, T> {
+    fn from(repo: &'a Repo<T>) -> RepoRef<'a, T> {
+        RepoRef {
+            repo,
+            owner: None,
+            _t: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, T: Contents> From<&'b RepoRef<'a, T>> for RepoRef<'a, T> {
+    fn from(repo: &'b RepoRef<'a, T>) -> RepoRef<'a, T> {
+        repo.clone()
+    }
+}
+
+impl<T: Contents + 'static> From<Arc<Repo<T>>> for RepoRef<'static, T> {
+    fn from(repo: Arc<Repo<T>>) -> RepoRef<'static, T> {
+        RepoRef {
+            repo: Arc::as_ptr(&repo),
+            owner: Some(repo),
+            _t: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'b, T: Contents + 'static> From<&'b Arc<Repo<T>>> for RepoRef<'static, T> {
+    fn from(repo: &'b Arc<Repo<T>>) -> RepoRef<'static, T> {
+        RepoRef::from(repo.clone())
+    }
+}
+
 #[derive(Clone)]
 pub struct UntypedRepo<'a> {
     pub(crate) ptr: *mut IrminRepo,
+    pub(crate) hash_type: HashType,
+    pub(crate) context: ContentsContext,
+    #[allow(dead_code)]
+    pub(crate) owner: Option<Arc<dyn std::any::Any>>,
     pub(crate) _t: std::marker::PhantomData<&'a ()>,
 }
 
 impl<'a> UntypedRepo<'a> {
-    pub fn new<T: Contents>(repo: &'a Repo<T>) -> UntypedRepo<'a> {
+    pub fn new<T: Contents>(repo: impl Into<RepoRef<'a, T>>) -> UntypedRepo<'a> {
+        let repo = repo.into();
         UntypedRepo {
             ptr: repo.ptr,
+            hash_type: repo.config.hash_type,
+            context: repo.config.context.clone(),
+            owner: repo.owner.clone(),
             _t: std::marker::PhantomData,
         }
     }
 }
+
+// Implements `Deref`, `DerefMut` and `From<T>` for contents that wrap a single value of type `T`
+// stored in `$field`, `$new` creates the wrapper from a `T`. Wrappers with extra type
+// parameters that can't derive `Clone`, `PartialEq` and `Debug` can also list those traits
+macro_rules! contents_wrapper {
+    ($name:ident [$($g:tt)*] $ty:ty, $field:tt, $new:expr) => {
+        impl<$($g)*> std::ops::Deref for $ty {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.$field
+            }
+        }
+
+        impl<$($g)*> std::ops::DerefMut for $ty {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.$field
+            }
+        }
+
+        impl<$($g)*> From<T> for $ty {
+            fn from(x: T) -> Self {
+                $new(x)
+            }
+        }
+    };
+    ($name:ident [$($g:tt)*] $ty:ty, $field:tt, $new:expr, Clone, PartialEq, Debug) => {
+        contents_wrapper!($name [$($g)*] $ty, $field, $new);
+
+        impl<$($g)*> Clone for $ty
+        where
+            T: Clone,
+        {
+            fn clone(&self) -> Self {
+                $new(self.$field.clone())
+            }
+        }
+
+        impl<$($g)*> PartialEq for $ty
+        where
+            T: PartialEq,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.$field == other.$field
+            }
+        }
+
+        impl<$($g)*> std::fmt::Debug for $ty
+        where
+            T: std::fmt::Debug,
+        {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+                fmt.debug_tuple(stringify!($name)).field(&self.$field).finish()
+            }
+        }
+    };
+}
+
+pub(crate) use contents_wrapper;
@@ -97,6 +97,19 @@ impl Value {
         Ok(Value { ptr, ty })
     }
 
+    /// OCaml option, `ty` is the type of the inner value
+    pub fn option(ty: &Type, v: Option<&Value>) -> Result<Value, Error> {
+        let ptr =
+            unsafe { irmin_value_option(v.map(|x| x.ptr).unwrap_or_else(std::ptr::null_mut)) };
+        if ptr.is_null() {
+            return Err(Error::NullPtr);
+        };
+
+        let ty = Type::option(ty)?;
+
+        Ok(Value { ptr, ty })
+    }
+
     /// Parse a value of the specified type from Irmin's string encoding
     pub fn of_string(ty: Type, s: impl AsRef<str>) -> Result<Value, Error> {
         let s = s.as_ref();
@@ -147,9 +160,41 @@ impl Value {
         crate::IrminString::wrap(s)
     }
 
+    /// Build a value of the specified type from any `serde::Serialize` type by serializing it
+    /// to JSON and parsing the result using `Value::of_json`. This is not a serde data format
+    /// for `Type`, libirmin can't inspect types or build records, so the Rust type must
+    /// serialize to the same JSON as Irmin's JSON encoding of `ty`: options as `null` or the
+    /// inner value, lists, arrays, pairs and triples as JSON arrays and records as objects with
+    /// matching field names
+    pub fn of_serde<S: serde::Serialize + ?Sized>(ty: Type, x: &S) -> Result<Value, Error> {
+        let s = serde_json::to_string(x)?;
+        Value::of_json(ty, s)
+    }
+
+    /// Read a value into any `serde::Deserialize` type by deserializing Irmin's JSON encoding
+    /// of the value, see `Value::of_serde`
+    pub fn to_serde<D: serde::de::DeserializeOwned>(&self) -> Result<D, Error> {
+        let s = self.to_json()?;
+        serde_json::from_slice(s.as_slice()).map_err(Error::from)
+    }
+
     /// Get IrminString from string value
     pub fn get_string(&self) -> Result<IrminString, Error> {
         let s = unsafe { irmin_value_get_string(self.ptr) };
         crate::IrminString::wrap(s)
     }
+
+    /// Get bytes from string value, the contents are not required to be valid UTF-8
+    pub fn get_bytes(&self) -> Result<Vec<u8>, Error> {
+        self.get_string().map(|x| x.as_slice().to_vec())
+    }
+}
+
+impl serde::Serialize for Value {
+    /// Values are serialized using Irmin's JSON encoding, see `Value::to_serde`
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+        let v: JsonValue = self.to_serde().map_err(S::Error::custom)?;
+        v.serialize(s)
+    }
 }